@@ -1,46 +1,172 @@
 extern crate proc_macro;
 
 use crate::proc_macro::TokenStream;
+use syn::spanned::Spanned;
+
+//the representations of #[repr(...)], packed(1) counts as packed
+fn reprs(attrs: &[syn::Attribute]) -> syn::Result<Vec<String>>
+{
+    let mut reprs = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta|
+        {
+            let ident = meta.path.get_ident().ok_or_else(|| meta.error("Unknown representation."))?.to_string();
+            let mut arg = None;
+            if meta.input.peek(syn::token::Paren)
+            {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                arg = Some(content.parse::<syn::LitInt>()?.base10_parse::<u32>()?);
+            }
+            if ident != "packed" || arg.is_none() || arg == Some(1) { reprs.push(ident); }
+            Ok(())
+        })?;
+    }
+    Ok(reprs)
+}
+
+fn named_fields(input: &syn::DeriveInput) -> syn::Result<&syn::punctuated::Punctuated<syn::Field, syn::Token![,]>>
+{
+    match &input.data
+    {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) => Ok(&fields.named),
+        syn::Data::Struct(data) => Err(syn::Error::new(data.fields.span(), "Only named fields allowed.")),
+        _ => Err(syn::Error::new(input.ident.span(), "Only structs allowed."))
+    }
+}
+
+//the element type and GLSL array suffix of a field type, the GLSL type is taken from the element type itself
+fn glsl_field(ty: &syn::Type) -> syn::Result<(&syn::Type, String)>
+{
+    match ty
+    {
+        syn::Type::Array(array) =>
+        {
+            let (elem, suffix) = glsl_field(&array.elem)?;
+            let len = match &array.len
+            {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(len), .. }) => len.base10_digits().to_string(),
+                len => return Err(syn::Error::new(len.span(), "GLSL declarations need a literal array length."))
+            };
+            //GLSL lists the outermost dimension first
+            Ok((elem, format!("[{len}]{suffix}")))
+        },
+        _ => Ok((ty, String::new()))
+    }
+}
+
+//a member declaration, left out for padding types
+fn glsl_member(glsl_type: &proc_macro2::TokenStream, head: &str, tail: &str) -> proc_macro2::TokenStream
+{
+    quote::quote!(::gru_vulkan::glsl_member(#glsl_type, #head), #glsl_type, ::gru_vulkan::glsl_member(#glsl_type, #tail),)
+}
+
+//concatenates the GLSL parts at compile time
+fn glsl_concat(parts: &proc_macro2::TokenStream) -> proc_macro2::TokenStream
+{
+    quote::quote!
+    {{
+        const PARTS: &[&str] = &[#parts];
+        const GLSL: [u8; ::gru_vulkan::glsl_len(PARTS)] = ::gru_vulkan::glsl_concat(PARTS);
+        match ::core::str::from_utf8(&GLSL)
+        {
+            Ok(glsl) => glsl,
+            Err(_) => panic!("invalid GLSL")
+        }
+    }}
+}
+
+//the optional #[glsl(set = ?, binding = ?, instance = "?")] attribute of blocks, the block is returned without its members
+fn glsl_block(attrs: &[syn::Attribute], name: &syn::Ident, layout_name: &str, storage: &str) -> syn::Result<(String, String)>
+{
+    let mut qualifiers = Vec::new();
+    let mut instance = String::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("glsl"))
+    {
+        attr.parse_nested_meta(|meta|
+        {
+            if meta.path.is_ident("set") || meta.path.is_ident("binding")
+            {
+                let value: syn::LitInt = meta.value()?.parse()?;
+                qualifiers.push(format!("{} = {}", meta.path.get_ident().unwrap(), value.base10_digits()));
+                Ok(())
+            }
+            else if meta.path.is_ident("instance")
+            {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                instance = format!(" {}", value.value());
+                Ok(())
+            }
+            else { Err(meta.error("Only the pattern \"glsl(set = ?, binding = ?, instance = \"?\")\" allowed.")) }
+        })?;
+    }
+    qualifiers.push(layout_name.to_string());
+    Ok((format!("layout({}) {storage} {name}\n{{\n", qualifiers.join(", ")), format!("}}{instance};\n")))
+}
 
 fn attribute_group_derive(input: TokenStream, rate: proc_macro2::TokenStream) -> TokenStream
 {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    attribute_group_impl(&input, rate).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn attribute_group_impl(input: &syn::DeriveInput, rate: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream>
+{
     let name = &input.ident;
+    if !input.generics.params.is_empty() { return Err(syn::Error::new(input.generics.span(), "Generic structs are not supported.")); }
+    //packing is optional, the offsets are taken from the struct itself
+    if !reprs(&input.attrs)?.iter().any(|repr| repr == "C") { return Err(syn::Error::new(name.span(), "Only #[repr(C)] structs allowed.")); }
     let mut comps = quote::quote!();
-    if let syn::Data::Struct(data) = input.data
+    let mut glsl = quote::quote!();
+    for field in named_fields(input)?
     {
-        if let syn::Fields::Named(fields) = data.fields
+        let ident = field.ident.as_ref().unwrap();
+        //other attributes like docs or serde are left alone, fields without location are no shader inputs
+        let mut locations = field.attrs.iter().filter(|attr| attr.path().is_ident("location"));
+        let Some(attr) = locations.next() else { continue };
+        if let Some(attr) = locations.next() { return Err(syn::Error::new(attr.span(), "Duplicate attribute \"location = ?\".")); }
+        let location: u32 = match &attr.meta
         {
-            for field in fields.named
-            {
-                if !field.ident.is_some() { panic!("Only named fields allowed."); }
-                if field.attrs.len() != 1 { panic!("Only the pattern \"location = ?\" allowed."); }
-                let attr = &field.attrs[0];
-                if attr.path().is_ident("location")
-                {
-                    if let syn::Meta::NameValue(syn::MetaNameValue { value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(value), .. }), ..}) = &attr.meta
-                    {
-                        let location: u32 = value.base10_parse().expect("Only the pattern \"location = ?\" allowed.");
-                        if let syn::Type::Path(path) = &field.ty
-                        {
-                            let ty = &path.path.segments.iter().nth(0).expect("Only the pattern \"location = ?\" allowed.").ident;
-                            comps.extend(quote::quote!((AttributeLocation(#location), #ty::TYPE),));
-                        } else { panic!("Only the pattern \"location = ?\" allowed."); }
-                    }
-                }
-            }
-        } else { panic!("Only named fields allowed."); }
-    } else { panic!("Only structs allowed."); }
+            syn::Meta::NameValue(syn::MetaNameValue { value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(value), .. }), ..}) => value.base10_parse()?,
+            meta => return Err(syn::Error::new(meta.span(), "Only the pattern \"location = ?\" allowed."))
+        };
+        let ty = &field.ty;
+        comps.extend(quote::quote_spanned!(ty.span() => (::gru_vulkan::AttributeLocation(#location), <#ty as ::gru_vulkan::AttributeTypeOf>::TYPE, ::core::mem::offset_of!(#name, #ident) as u32),));
+        glsl.extend(glsl_member(&quote::quote!(<#ty as ::gru_vulkan::AttributeTypeOf>::GLSL_TYPE), &format!("layout(location = {location}) in "), &format!(" {ident};\n")));
+    }
+    let glsl = glsl_concat(&glsl);
+    let overlap_message = format!("the attribute locations of `{name}` overlap, matrices take up one location per column");
     let expanded = quote::quote!
     {
-        impl AttributeGroupReprCpacked for #name
+        impl ::gru_vulkan::AttributeGroupReprCpacked for #name
         {
-            const RATE: InputRate = InputRate::#rate;
-            const ATTRIBUTES: &'static [(AttributeLocation, AttributeType)] = &[#comps];
+            const RATE: ::gru_vulkan::InputRate = ::gru_vulkan::InputRate::#rate;
+            const ATTRIBUTES: &'static [(::gru_vulkan::AttributeLocation, ::gru_vulkan::AttributeType, u32)] = &[#comps];
+            const STRIDE: u32 = ::core::mem::size_of::<#name>() as u32;
+            const GLSL: &'static str = #glsl;
         }
+
+        //matrices take up several locations, these must not overlap with the next attribute
+        const _: () =
+        {
+            let attributes = <#name as ::gru_vulkan::AttributeGroupReprCpacked>::ATTRIBUTES;
+            let mut i = 0;
+            while i < attributes.len()
+            {
+                let mut j = 0;
+                while j < attributes.len()
+                {
+                    let (first, second) = (&attributes[i], &attributes[j]);
+                    assert!(i == j || second.0.0 < first.0.0 || second.0.0 >= first.0.0 + first.1.locations(), #overlap_message);
+                    j += 1;
+                }
+                i += 1;
+            }
+        };
     };
     //println!("{}", expanded);
-    TokenStream::from(expanded)
+    Ok(expanded)
 }
 
 #[proc_macro_derive(VertexAttributeGroupReprCpacked, attributes(location))]
@@ -62,7 +188,7 @@ pub fn storage_struct_derive(input: TokenStream) -> TokenStream
     let name = &input.ident;
     let expanded = quote::quote!
     {
-        impl StorageStructReprC for #name { }
+        impl ::gru_vulkan::StorageStructReprC for #name { }
     };
     //println!("{}", expanded);
     TokenStream::from(expanded)
@@ -75,7 +201,7 @@ pub fn descriptor_struct_derive(input: TokenStream) -> TokenStream
     let name = &input.ident;
     let expanded = quote::quote!
     {
-        impl DescriptorStructReprC for #name { }
+        impl ::gru_vulkan::DescriptorStructReprC for #name { }
     };
     //println!("{}", expanded);
     TokenStream::from(expanded)
@@ -90,9 +216,111 @@ pub fn attribute_type_derive(input: TokenStream) -> TokenStream
     {
         impl #name
         {
-            pub const TYPE: AttributeType = AttributeType::#name;
+            pub const TYPE: ::gru_vulkan::AttributeType = ::gru_vulkan::AttributeType::#name;
+        }
+
+        impl ::gru_vulkan::AttributeTypeOf for #name
+        {
+            const TYPE: ::gru_vulkan::AttributeType = ::gru_vulkan::AttributeType::#name;
         }
     };
     //println!("{}", expanded);
     TokenStream::from(expanded)
 }
+
+fn layout_derive(input: TokenStream, std140: bool) -> TokenStream
+{
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    layout_impl(&input, std140).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn layout_impl(input: &syn::DeriveInput, std140: bool) -> syn::Result<proc_macro2::TokenStream>
+{
+    let name = &input.ident;
+    let (layout, layout_name, descriptor_trait) = if std140 { (quote::quote!(::gru_vulkan::Std140), "std140", quote::quote!(::gru_vulkan::DescriptorStructReprC)) } else { (quote::quote!(::gru_vulkan::Std430), "std430", quote::quote!(::gru_vulkan::StorageStructReprC)) };
+    if !input.generics.params.is_empty() { return Err(syn::Error::new(input.generics.span(), "Generic structs are not supported.")); }
+    if !reprs(&input.attrs)?.iter().any(|repr| repr == "C") { return Err(syn::Error::new(name.span(), "Only #[repr(C)] structs allowed.")); }
+    //the GLSL offset of every field is computed at compile time and compared to the Rust offset
+    let mut checks = quote::quote!();
+    let mut aligns = Vec::new();
+    let mut sizes = Vec::new();
+    let mut members = quote::quote!();
+    let mut nested: Vec<&syn::Type> = Vec::new();
+    for field in named_fields(input)?
+    {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let offset_message = format!("{layout_name}: field `{ident}` of `{name}` is not at its GLSL offset, insert padding before it");
+        let size_message = format!("{layout_name}: the size of field `{ident}` of `{name}` differs from its GLSL size");
+        checks.extend(quote::quote_spanned!
+        {
+            field.span() =>
+            offset = offset.div_ceil(<#ty as #layout>::ALIGN) * <#ty as #layout>::ALIGN;
+            assert!(offset == ::core::mem::offset_of!(#name, #ident), #offset_message);
+            assert!(::core::mem::size_of::<#ty>() == <#ty as #layout>::SIZE, #size_message);
+            offset += <#ty as #layout>::SIZE;
+        });
+        let (elem, suffix) = glsl_field(ty)?;
+        members.extend(glsl_member(&quote::quote!(<#elem as #layout>::GLSL_TYPE), "    ", &format!(" {ident}{suffix};\n")));
+        //only nested structs have a declaration, it is prepended once
+        if !nested.iter().any(|other| quote::quote!(#other).to_string() == quote::quote!(#elem).to_string()) { nested.push(elem); }
+        aligns.push(quote::quote!(<#ty as #layout>::ALIGN));
+        sizes.push(quote::quote!(<#ty as #layout>::SIZE));
+    }
+    //std140 rounds the alignment of structs up to a vec4
+    let min_align: usize = if std140 { 16 } else { 1 };
+    let (block_head, block_tail) = glsl_block(&input.attrs, name, layout_name, if std140 { "uniform" } else { "buffer" })?;
+    //include guards keep structs nested along several paths from being declared twice
+    let name_str = name.to_string();
+    let guard = format!("GRU_VULKAN_STRUCT_{name}");
+    let (struct_head, struct_tail) = (format!("#ifndef {guard}\n#define {guard}\nstruct {name}\n{{\n"), String::from("};\n#endif\n"));
+    //the declarations of the nested structs are prepended at compile time
+    let concat = |head: &str, tail: &str| glsl_concat(&quote::quote!(#(<#nested as #layout>::GLSL_STRUCT,)* #head, #members #tail));
+    let (glsl, glsl_struct) = (concat(&block_head, &block_tail), concat(&struct_head, &struct_tail));
+    let size_message = format!("{layout_name}: the size of `{name}` differs from its GLSL size, add padding at the end");
+    let expanded = quote::quote!
+    {
+        unsafe impl #layout for #name
+        {
+            const ALIGN: usize =
+            {
+                let mut align = #min_align;
+                #(if #aligns > align { align = #aligns; })*
+                align
+            };
+            const SIZE: usize =
+            {
+                let mut offset = 0usize;
+                #(offset = offset.div_ceil(#aligns) * #aligns + #sizes;)*
+                offset.div_ceil(<Self as #layout>::ALIGN) * <Self as #layout>::ALIGN
+            };
+            const GLSL_TYPE: &'static str = #name_str;
+            const GLSL: &'static str = #glsl;
+            const GLSL_STRUCT: &'static str = #glsl_struct;
+        }
+
+        impl #descriptor_trait for #name { }
+
+        const _: () =
+        {
+            let mut offset = 0usize;
+            #checks
+            let _ = offset;
+            assert!(::core::mem::size_of::<#name>() == <#name as #layout>::SIZE, #size_message);
+        };
+    };
+    //println!("{}", expanded);
+    Ok(expanded)
+}
+
+#[proc_macro_derive(Std140, attributes(glsl))]
+pub fn std140_derive(input: TokenStream) -> TokenStream
+{
+    layout_derive(input, true)
+}
+
+#[proc_macro_derive(Std430, attributes(glsl))]
+pub fn std430_derive(input: TokenStream) -> TokenStream
+{
+    layout_derive(input, false)
+}
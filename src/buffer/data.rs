@@ -1,6 +1,6 @@
 use super::*;
 #[cfg(feature = "math")]
-use gru_misc::math::{Vec2, Vec3, Vec4};
+use gru_misc::math::{Vec2, Vec3, Vec4, Mat2, Mat3, Mat4};
 
 impl IndexType for u16
 {
@@ -12,6 +12,52 @@ impl IndexType for u32
     const FORMAT: vk::IndexType = vk::IndexType::UINT32;
 }
 
+//     #####     INDIRECT COMMANDS
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(C)]
+pub struct DrawIndirectCommand
+{
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub first_vertex: u32,
+    pub first_instance: u32
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(C)]
+pub struct DrawIndexedIndirectCommand
+{
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub vertex_offset: i32,
+    pub first_instance: u32
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(C)]
+pub struct DispatchIndirectCommand
+{
+    pub x: u32,
+    pub y: u32,
+    pub z: u32
+}
+
+//the number of draws for the count variants
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(C)]
+pub struct IndirectCount(pub u32);
+
+impl StorageStructReprC for DrawIndirectCommand { }
+impl StorageStructReprC for DrawIndexedIndirectCommand { }
+impl StorageStructReprC for DispatchIndirectCommand { }
+impl StorageStructReprC for IndirectCount { }
+
 impl AttributeType
 {
     pub(crate) const fn vk_format(&self) -> vk::Format
@@ -29,7 +75,24 @@ impl AttributeType
             AttributeType::U1 => vk::Format::R32_UINT,
             AttributeType::U2 => vk::Format::R32G32_UINT,
             AttributeType::U3 => vk::Format::R32G32B32_UINT,
-            AttributeType::U4 => vk::Format::R32G32B32A32_UINT
+            AttributeType::U4 => vk::Format::R32G32B32A32_UINT,
+            AttributeType::Unorm8x4 => vk::Format::R8G8B8A8_UNORM,
+            AttributeType::Snorm8x4 => vk::Format::R8G8B8A8_SNORM,
+            AttributeType::Unorm16x2 => vk::Format::R16G16_UNORM,
+            AttributeType::Unorm16x4 => vk::Format::R16G16B16A16_UNORM,
+            AttributeType::Snorm16x2 => vk::Format::R16G16_SNORM,
+            AttributeType::Snorm16x4 => vk::Format::R16G16B16A16_SNORM,
+            AttributeType::U16x2 => vk::Format::R16G16_UINT,
+            AttributeType::U16x4 => vk::Format::R16G16B16A16_UINT,
+            AttributeType::I16x2 => vk::Format::R16G16_SINT,
+            AttributeType::I16x4 => vk::Format::R16G16B16A16_SINT,
+            AttributeType::F16x2 => vk::Format::R16G16_SFLOAT,
+            AttributeType::F16x4 => vk::Format::R16G16B16A16_SFLOAT,
+            AttributeType::A2Bgr10Unorm => vk::Format::A2B10G10R10_UNORM_PACK32,
+            AttributeType::A2Bgr10Snorm => vk::Format::A2B10G10R10_SNORM_PACK32,
+            //the format of a single column, M3 columns are padded
+            AttributeType::M2 => vk::Format::R32G32_SFLOAT,
+            AttributeType::M3 | AttributeType::M3x4 | AttributeType::M4 => vk::Format::R32G32B32A32_SFLOAT
         }
     }
 
@@ -41,6 +104,50 @@ impl AttributeType
             AttributeType::F2 | AttributeType::I2 | AttributeType::U2 => 8,
             AttributeType::F3 | AttributeType::I3 | AttributeType::U3 => 12,
             AttributeType::F4 | AttributeType::I4 | AttributeType::U4 => 16,
+            AttributeType::Unorm8x4 | AttributeType::Snorm8x4 => 4,
+            AttributeType::Unorm16x2 | AttributeType::Snorm16x2 | AttributeType::U16x2 | AttributeType::I16x2 | AttributeType::F16x2 => 4,
+            AttributeType::Unorm16x4 | AttributeType::Snorm16x4 | AttributeType::U16x4 | AttributeType::I16x4 | AttributeType::F16x4 => 8,
+            AttributeType::A2Bgr10Unorm | AttributeType::A2Bgr10Snorm => 4,
+            AttributeType::M2 => 16,
+            AttributeType::M3 | AttributeType::M3x4 => 48,
+            AttributeType::M4 => 64
+        }
+    }
+
+    //the type of the matching shader input, normalized and packed types are read as floats
+    pub const fn glsl_type(&self) -> &'static str
+    {
+        match self
+        {
+            AttributeType::F1 => "float",
+            AttributeType::F2 | AttributeType::Unorm16x2 | AttributeType::Snorm16x2 | AttributeType::F16x2 => "vec2",
+            AttributeType::F3 => "vec3",
+            AttributeType::F4 | AttributeType::Unorm8x4 | AttributeType::Snorm8x4 | AttributeType::Unorm16x4 | AttributeType::Snorm16x4 | AttributeType::F16x4 => "vec4",
+            AttributeType::A2Bgr10Unorm | AttributeType::A2Bgr10Snorm => "vec4",
+            AttributeType::I1 => "int",
+            AttributeType::I2 | AttributeType::I16x2 => "ivec2",
+            AttributeType::I3 => "ivec3",
+            AttributeType::I4 | AttributeType::I16x4 => "ivec4",
+            AttributeType::U1 => "uint",
+            AttributeType::U2 | AttributeType::U16x2 => "uvec2",
+            AttributeType::U3 => "uvec3",
+            AttributeType::U4 | AttributeType::U16x4 => "uvec4",
+            AttributeType::M2 => "mat2",
+            AttributeType::M3 => "mat3",
+            AttributeType::M3x4 => "mat3x4",
+            AttributeType::M4 => "mat4"
+        }
+    }
+
+    //the number of consecutive locations taken up
+    pub const fn locations(&self) -> u32
+    {
+        match self
+        {
+            AttributeType::M2 => 2,
+            AttributeType::M3 | AttributeType::M3x4 => 3,
+            AttributeType::M4 => 4,
+            _ => 1
         }
     }
 }
@@ -308,3 +415,570 @@ impl Into<(u32, u32, u32, u32)> for U4
     #[inline]
     fn into(self) -> (u32, u32, u32, u32) { (self.0, self.1, self.2, self.3) }
 }
+
+//     #####     NORMALIZED, HALF AND PACKED
+
+#[inline]
+fn unorm(x: f32, max: f32) -> f32 { (x.clamp(0.0, 1.0) * max).round() }
+
+#[inline]
+fn snorm(x: f32, max: f32) -> f32 { (x.clamp(-1.0, 1.0) * max).round() }
+
+//round to nearest even, overflow becomes infinity
+fn f16_bits(x: f32) -> u16
+{
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7fffff;
+    if exponent == 0xff { return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 }; }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f { return sign | 0x7c00; }
+    let (half, shift) = if exponent <= 0
+    {
+        if exponent < -10 { return sign; }
+        let shift = (14 - exponent) as u32;
+        ((mantissa | 0x800000) >> shift, shift)
+    } else { (((exponent as u32) << 10) | (mantissa >> 13), 13) };
+    let remainder = (mantissa | if exponent <= 0 { 0x800000 } else { 0 }) & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let half = if remainder > halfway || (remainder == halfway && half & 1 == 1) { half + 1 } else { half };
+    sign | half as u16
+}
+
+fn f32_from_bits(half: u16) -> f32
+{
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exponent = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+    match exponent
+    {
+        0 =>
+        {
+            let x = mantissa as f32 / (1 << 24) as f32;
+            if sign == 0 { x } else { -x }
+        },
+        0x1f => f32::from_bits(sign | 0x7f800000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13))
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct Unorm8x4(u8, u8, u8, u8);
+
+impl From<(u8, u8, u8, u8)> for Unorm8x4
+{
+    #[inline]
+    fn from((x, y, z, w): (u8, u8, u8, u8)) -> Self { Unorm8x4(x, y, z, w) }
+}
+
+impl From<(f32, f32, f32, f32)> for Unorm8x4
+{
+    #[inline]
+    fn from((x, y, z, w): (f32, f32, f32, f32)) -> Self { Unorm8x4(unorm(x, 255.0) as u8, unorm(y, 255.0) as u8, unorm(z, 255.0) as u8, unorm(w, 255.0) as u8) }
+}
+
+impl Into<(u8, u8, u8, u8)> for Unorm8x4
+{
+    #[inline]
+    fn into(self) -> (u8, u8, u8, u8) { (self.0, self.1, self.2, self.3) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct Snorm8x4(i8, i8, i8, i8);
+
+impl From<(i8, i8, i8, i8)> for Snorm8x4
+{
+    #[inline]
+    fn from((x, y, z, w): (i8, i8, i8, i8)) -> Self { Snorm8x4(x, y, z, w) }
+}
+
+impl From<(f32, f32, f32, f32)> for Snorm8x4
+{
+    #[inline]
+    fn from((x, y, z, w): (f32, f32, f32, f32)) -> Self { Snorm8x4(snorm(x, 127.0) as i8, snorm(y, 127.0) as i8, snorm(z, 127.0) as i8, snorm(w, 127.0) as i8) }
+}
+
+impl Into<(i8, i8, i8, i8)> for Snorm8x4
+{
+    #[inline]
+    fn into(self) -> (i8, i8, i8, i8) { (self.0, self.1, self.2, self.3) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct Unorm16x2(u16, u16);
+
+impl From<(u16, u16)> for Unorm16x2
+{
+    #[inline]
+    fn from((x, y): (u16, u16)) -> Self { Unorm16x2(x, y) }
+}
+
+impl From<(f32, f32)> for Unorm16x2
+{
+    #[inline]
+    fn from((x, y): (f32, f32)) -> Self { Unorm16x2(unorm(x, 65535.0) as u16, unorm(y, 65535.0) as u16) }
+}
+
+impl Into<(u16, u16)> for Unorm16x2
+{
+    #[inline]
+    fn into(self) -> (u16, u16) { (self.0, self.1) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct Unorm16x4(u16, u16, u16, u16);
+
+impl From<(u16, u16, u16, u16)> for Unorm16x4
+{
+    #[inline]
+    fn from((x, y, z, w): (u16, u16, u16, u16)) -> Self { Unorm16x4(x, y, z, w) }
+}
+
+impl From<(f32, f32, f32, f32)> for Unorm16x4
+{
+    #[inline]
+    fn from((x, y, z, w): (f32, f32, f32, f32)) -> Self { Unorm16x4(unorm(x, 65535.0) as u16, unorm(y, 65535.0) as u16, unorm(z, 65535.0) as u16, unorm(w, 65535.0) as u16) }
+}
+
+impl Into<(u16, u16, u16, u16)> for Unorm16x4
+{
+    #[inline]
+    fn into(self) -> (u16, u16, u16, u16) { (self.0, self.1, self.2, self.3) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct Snorm16x2(i16, i16);
+
+impl From<(i16, i16)> for Snorm16x2
+{
+    #[inline]
+    fn from((x, y): (i16, i16)) -> Self { Snorm16x2(x, y) }
+}
+
+impl From<(f32, f32)> for Snorm16x2
+{
+    #[inline]
+    fn from((x, y): (f32, f32)) -> Self { Snorm16x2(snorm(x, 32767.0) as i16, snorm(y, 32767.0) as i16) }
+}
+
+impl Into<(i16, i16)> for Snorm16x2
+{
+    #[inline]
+    fn into(self) -> (i16, i16) { (self.0, self.1) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct Snorm16x4(i16, i16, i16, i16);
+
+impl From<(i16, i16, i16, i16)> for Snorm16x4
+{
+    #[inline]
+    fn from((x, y, z, w): (i16, i16, i16, i16)) -> Self { Snorm16x4(x, y, z, w) }
+}
+
+impl From<(f32, f32, f32, f32)> for Snorm16x4
+{
+    #[inline]
+    fn from((x, y, z, w): (f32, f32, f32, f32)) -> Self { Snorm16x4(snorm(x, 32767.0) as i16, snorm(y, 32767.0) as i16, snorm(z, 32767.0) as i16, snorm(w, 32767.0) as i16) }
+}
+
+impl Into<(i16, i16, i16, i16)> for Snorm16x4
+{
+    #[inline]
+    fn into(self) -> (i16, i16, i16, i16) { (self.0, self.1, self.2, self.3) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct U16x2(u16, u16);
+
+impl From<(u16, u16)> for U16x2
+{
+    #[inline]
+    fn from((x, y): (u16, u16)) -> Self { U16x2(x, y) }
+}
+
+impl Into<(u16, u16)> for U16x2
+{
+    #[inline]
+    fn into(self) -> (u16, u16) { (self.0, self.1) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct U16x4(u16, u16, u16, u16);
+
+impl From<(u16, u16, u16, u16)> for U16x4
+{
+    #[inline]
+    fn from((x, y, z, w): (u16, u16, u16, u16)) -> Self { U16x4(x, y, z, w) }
+}
+
+impl Into<(u16, u16, u16, u16)> for U16x4
+{
+    #[inline]
+    fn into(self) -> (u16, u16, u16, u16) { (self.0, self.1, self.2, self.3) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct I16x2(i16, i16);
+
+impl From<(i16, i16)> for I16x2
+{
+    #[inline]
+    fn from((x, y): (i16, i16)) -> Self { I16x2(x, y) }
+}
+
+impl Into<(i16, i16)> for I16x2
+{
+    #[inline]
+    fn into(self) -> (i16, i16) { (self.0, self.1) }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct I16x4(i16, i16, i16, i16);
+
+impl From<(i16, i16, i16, i16)> for I16x4
+{
+    #[inline]
+    fn from((x, y, z, w): (i16, i16, i16, i16)) -> Self { I16x4(x, y, z, w) }
+}
+
+impl Into<(i16, i16, i16, i16)> for I16x4
+{
+    #[inline]
+    fn into(self) -> (i16, i16, i16, i16) { (self.0, self.1, self.2, self.3) }
+}
+
+//the raw bits of two halfs
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct F16x2(u16, u16);
+
+impl From<(f32, f32)> for F16x2
+{
+    #[inline]
+    fn from((x, y): (f32, f32)) -> Self { F16x2(f16_bits(x), f16_bits(y)) }
+}
+
+impl Into<(f32, f32)> for F16x2
+{
+    #[inline]
+    fn into(self) -> (f32, f32) { (f32_from_bits(self.0), f32_from_bits(self.1)) }
+}
+
+#[cfg(feature = "math")]
+impl From<Vec2> for F16x2
+{
+    #[inline]
+    fn from(Vec2(x, y): Vec2) -> Self { F16x2(f16_bits(x), f16_bits(y)) }
+}
+
+//the raw bits of four halfs
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct F16x4(u16, u16, u16, u16);
+
+impl From<(f32, f32, f32, f32)> for F16x4
+{
+    #[inline]
+    fn from((x, y, z, w): (f32, f32, f32, f32)) -> Self { F16x4(f16_bits(x), f16_bits(y), f16_bits(z), f16_bits(w)) }
+}
+
+impl Into<(f32, f32, f32, f32)> for F16x4
+{
+    #[inline]
+    fn into(self) -> (f32, f32, f32, f32) { (f32_from_bits(self.0), f32_from_bits(self.1), f32_from_bits(self.2), f32_from_bits(self.3)) }
+}
+
+#[cfg(feature = "math")]
+impl From<Vec4> for F16x4
+{
+    #[inline]
+    fn from(Vec4(x, y, z, w): Vec4) -> Self { F16x4(f16_bits(x), f16_bits(y), f16_bits(z), f16_bits(w)) }
+}
+
+//x in the lowest 10 bits, w in the highest 2 bits
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct A2Bgr10Unorm(u32);
+
+impl From<(f32, f32, f32, f32)> for A2Bgr10Unorm
+{
+    #[inline]
+    fn from((x, y, z, w): (f32, f32, f32, f32)) -> Self
+    {
+        A2Bgr10Unorm(unorm(x, 1023.0) as u32 | (unorm(y, 1023.0) as u32) << 10 | (unorm(z, 1023.0) as u32) << 20 | (unorm(w, 3.0) as u32) << 30)
+    }
+}
+
+impl Into<u32> for A2Bgr10Unorm
+{
+    #[inline]
+    fn into(self) -> u32 { self.0 }
+}
+
+//packed normals, w is 0
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct A2Bgr10Snorm(u32);
+
+impl From<(f32, f32, f32)> for A2Bgr10Snorm
+{
+    #[inline]
+    fn from((x, y, z): (f32, f32, f32)) -> Self
+    {
+        A2Bgr10Snorm((snorm(x, 511.0) as i32 as u32 & 0x3ff) | (snorm(y, 511.0) as i32 as u32 & 0x3ff) << 10 | (snorm(z, 511.0) as i32 as u32 & 0x3ff) << 20)
+    }
+}
+
+#[cfg(feature = "math")]
+impl From<Vec3> for A2Bgr10Snorm
+{
+    #[inline]
+    fn from(Vec3(x, y, z): Vec3) -> Self { (x, y, z).into() }
+}
+
+impl Into<u32> for A2Bgr10Snorm
+{
+    #[inline]
+    fn into(self) -> u32 { self.0 }
+}
+
+//     #####     M2 - M4
+
+//matrices are also attributes, every column takes up one location
+
+//column major
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct M2(pub [[f32; 2]; 2]);
+
+impl From<[[f32; 2]; 2]> for M2
+{
+    #[inline]
+    fn from(columns: [[f32; 2]; 2]) -> Self { M2(columns) }
+}
+
+impl Into<[[f32; 2]; 2]> for M2
+{
+    #[inline]
+    fn into(self) -> [[f32; 2]; 2] { self.0 }
+}
+
+#[cfg(feature = "math")]
+impl From<[Vec2; 2]> for M2
+{
+    #[inline]
+    fn from(columns: [Vec2; 2]) -> Self { M2(columns.map(|Vec2(x, y)| [x, y])) }
+}
+
+#[cfg(feature = "math")]
+impl From<Mat2> for M2
+{
+    #[inline]
+    fn from(Mat2(a, b): Mat2) -> Self { [a, b].into() }
+}
+
+//column major, every column is padded to a vec4
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct M3(pub [[f32; 4]; 3]);
+
+impl From<[[f32; 3]; 3]> for M3
+{
+    #[inline]
+    fn from(columns: [[f32; 3]; 3]) -> Self { M3(columns.map(|[x, y, z]| [x, y, z, 0.0])) }
+}
+
+impl Into<[[f32; 3]; 3]> for M3
+{
+    #[inline]
+    fn into(self) -> [[f32; 3]; 3] { self.0.map(|[x, y, z, _]| [x, y, z]) }
+}
+
+#[cfg(feature = "math")]
+impl From<[Vec3; 3]> for M3
+{
+    #[inline]
+    fn from(columns: [Vec3; 3]) -> Self { M3(columns.map(|Vec3(x, y, z)| [x, y, z, 0.0])) }
+}
+
+#[cfg(feature = "math")]
+impl From<Mat3> for M3
+{
+    #[inline]
+    fn from(Mat3(a, b, c): Mat3) -> Self { [a, b, c].into() }
+}
+
+//column major, three columns with four rows
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct M3x4(pub [[f32; 4]; 3]);
+
+impl From<[[f32; 4]; 3]> for M3x4
+{
+    #[inline]
+    fn from(columns: [[f32; 4]; 3]) -> Self { M3x4(columns) }
+}
+
+impl Into<[[f32; 4]; 3]> for M3x4
+{
+    #[inline]
+    fn into(self) -> [[f32; 4]; 3] { self.0 }
+}
+
+#[cfg(feature = "math")]
+impl From<[Vec4; 3]> for M3x4
+{
+    #[inline]
+    fn from(columns: [Vec4; 3]) -> Self { M3x4(columns.map(|Vec4(x, y, z, w)| [x, y, z, w])) }
+}
+
+//column major
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(gru_vulkan_derive::AttributeType)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct M4(pub [[f32; 4]; 4]);
+
+impl From<[[f32; 4]; 4]> for M4
+{
+    #[inline]
+    fn from(columns: [[f32; 4]; 4]) -> Self { M4(columns) }
+}
+
+impl Into<[[f32; 4]; 4]> for M4
+{
+    #[inline]
+    fn into(self) -> [[f32; 4]; 4] { self.0 }
+}
+
+#[cfg(feature = "math")]
+impl From<[Vec4; 4]> for M4
+{
+    #[inline]
+    fn from(columns: [Vec4; 4]) -> Self { M4(columns.map(|Vec4(x, y, z, w)| [x, y, z, w])) }
+}
+
+#[cfg(feature = "math")]
+impl From<Mat4> for M4
+{
+    #[inline]
+    fn from(Mat4(a, b, c, d): Mat4) -> Self { [a, b, c, d].into() }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn f16_exact()
+    {
+        for (x, half) in [(0.0, 0x0000), (-0.0, 0x8000), (1.0, 0x3c00), (-2.0, 0xc000), (0.5, 0x3800), (65504.0, 0x7bff), (6.1035156e-5, 0x0400), (5.9604645e-8, 0x0001)]
+        {
+            assert_eq!(f16_bits(x), half, "{x}");
+            assert_eq!(f32_from_bits(half).to_bits(), (x as f32).to_bits(), "{half:#06x}");
+        }
+    }
+
+    #[test]
+    fn f16_rounding()
+    {
+        //halfway between 1.0 and the next half (1.0 + 2^-10) rounds to even
+        assert_eq!(f16_bits(1.0 + 2f32.powi(-11)), 0x3c00);
+        assert_eq!(f16_bits(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+        assert_eq!(f16_bits(1.0 + 2f32.powi(-11) + 2f32.powi(-20)), 0x3c01);
+        //subnormal halves
+        assert_eq!(f16_bits(1.5 * 2f32.powi(-24)), 0x0002);
+        assert_eq!(f16_bits(2f32.powi(-26)), 0x0000);
+        //rounding up into the next exponent
+        assert_eq!(f16_bits(65519.0), 0x7bff);
+        assert_eq!(f16_bits(65520.0), 0x7c00);
+    }
+
+    #[test]
+    fn f16_special()
+    {
+        assert_eq!(f16_bits(f32::INFINITY), 0x7c00);
+        assert_eq!(f16_bits(f32::NEG_INFINITY), 0xfc00);
+        assert_eq!(f16_bits(1e10), 0x7c00);
+        assert!(f32_from_bits(f16_bits(f32::NAN)).is_nan());
+        assert_eq!(f32_from_bits(0x7c00), f32::INFINITY);
+    }
+
+    #[test]
+    fn f16_round_trip()
+    {
+        for half in (0..=0xffffu16).filter(|half| half & 0x7c00 != 0x7c00)
+        {
+            assert_eq!(f16_bits(f32_from_bits(half)), half, "{half:#06x}");
+        }
+    }
+
+    #[test]
+    fn normalized()
+    {
+        let Unorm8x4(x, y, z, w) = (0.0, 1.0, 0.5, 2.0).into();
+        assert_eq!((x, y, z, w), (0, 255, 128, 255));
+        let Snorm8x4(x, y, z, w) = (-1.0, 1.0, 0.0, -3.0).into();
+        assert_eq!((x, y, z, w), (-127, 127, 0, -127));
+        let Unorm16x2(x, y) = (1.0, -1.0).into();
+        assert_eq!((x, y), (65535, 0));
+        let Snorm16x2(x, y) = (-0.5, 1.0).into();
+        assert_eq!((x, y), (-16384, 32767));
+    }
+
+    #[test]
+    fn packed()
+    {
+        let unorm: u32 = A2Bgr10Unorm::from((1.0, 0.0, 1.0, 1.0)).into();
+        assert_eq!(unorm, 0x3ff | 0x3ff << 20 | 3 << 30);
+        let snorm: u32 = A2Bgr10Snorm::from((-1.0, 1.0, 0.0)).into();
+        assert_eq!(snorm, 0x201 | 0x1ff << 10);
+    }
+}
@@ -0,0 +1,191 @@
+use super::*;
+
+impl Device
+{
+    //add is one of the BufferTypeBuilder::add_? functions, e.g. BufferTypeBuilder::add_attributes
+    pub fn new_gpu_vec<T>(&self, add: fn(&mut BufferTypeBuilder, u32) -> BufferView<T>, capacity: u32) -> Result<GpuVec<T>, Error>
+    {
+        let (buffer, view) = self.new_gpu_vec_buffer(add, capacity.max(1))?;
+        Ok(GpuVec
+        {
+            device: self.clone(),
+            add,
+            buffer,
+            view,
+            len: 0,
+            frame: 0,
+            retired: std::collections::VecDeque::new()
+        })
+    }
+
+    fn new_gpu_vec_buffer<T>(&self, add: fn(&mut BufferTypeBuilder, u32) -> BufferView<T>, capacity: u32) -> Result<(Buffer, BufferView<T>), Error>
+    {
+        let mut buffer_type = self.new_buffer_type();
+        let view = add(&mut buffer_type, capacity);
+        let buffer = self.new_buffer(&buffer_type.build(), BufferUsage::Dynamic)?;
+        Ok((buffer, view))
+    }
+}
+
+//the length after appending, None if it does not fit in a u32
+fn grown_len(len: u32, additional: usize) -> Option<u32>
+{
+    u32::try_from(len as usize + additional).ok()
+}
+
+//the capacity at least doubles, so pushing has amortized constant cost
+fn grown_capacity(capacity: u32, len: u32) -> u32
+{
+    len.max(capacity.saturating_mul(2))
+}
+
+//the generation of the buffer replacing one of the given generation, tracked buffers of older ones become stale
+fn next_generation(current_generation: &std::sync::atomic::AtomicU32, generation: u32) -> u32
+{
+    current_generation.store(generation + 1, std::sync::atomic::Ordering::Relaxed);
+    generation + 1
+}
+
+//frames finish in submission order, so everything retired up to the last use of frame is unused
+fn free_retired<T>(retired: &mut std::collections::VecDeque<(usize, T)>, frame: usize)
+{
+    if let Some(last) = retired.iter().rposition(|(retired, _)| *retired == frame) { retired.drain(..=last); }
+}
+
+impl<T> GpuVec<T>
+{
+    #[inline]
+    pub fn len(&self) -> u32 { self.len }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    #[inline]
+    pub fn capacity(&self) -> u32 { self.view.count }
+
+    //increased by every reallocation, descriptor sets updated with older generations are stale,
+    //index and attribute bindings borrow the buffer and cannot outlive a reallocation
+    #[inline]
+    pub fn generation(&self) -> u32 { self.buffer.generation }
+
+    #[inline]
+    pub fn buffer(&self) -> &Buffer { &self.buffer }
+
+    //covers the whole capacity, only the first len elements are valid
+    #[inline]
+    pub fn view(&self) -> &BufferView<T> { &self.view }
+
+    #[inline]
+    pub fn clear(&mut self)
+    {
+        self.len = 0;
+    }
+
+    //call with the index of the frame in flight (FrameRecord::frame) once its fence has been waited for,
+    //this frees the buffers replaced the last time the frame was used and all earlier ones
+    pub fn begin_frame(&mut self, frame: usize)
+    {
+        free_retired(&mut self.retired, frame);
+        self.frame = frame;
+    }
+
+    //the old buffer is kept until begin_frame is called for the current frame again
+    pub fn reserve(&mut self, capacity: u32) -> Result<(), Error>
+    {
+        if capacity <= self.view.count { return Ok(()); }
+        let (mut buffer, view) = self.device.new_gpu_vec_buffer(self.add, capacity)?;
+        let old_ptr = self.buffer.allocation.as_ref().unwrap().mapped_ptr().unwrap().as_ptr();
+        let new_ptr = buffer.allocation.as_ref().unwrap().mapped_ptr().unwrap().as_ptr();
+        unsafe
+        {
+            let old_ptr = old_ptr.add(self.view.offset_in_bytes) as *const u8;
+            let new_ptr = new_ptr.add(view.offset_in_bytes) as *mut u8;
+            new_ptr.copy_from_nonoverlapping(old_ptr, self.len as usize * self.view.stride as usize);
+        }
+        buffer.generation = next_generation(&self.buffer.current_generation, self.buffer.generation);
+        buffer.current_generation = self.buffer.current_generation.clone();
+        let old_buffer = std::mem::replace(&mut self.buffer, buffer);
+        self.retired.push_back((self.frame, old_buffer));
+        self.view = view;
+        Ok(())
+    }
+}
+
+impl<T: Copy> GpuVec<T>
+{
+    #[inline]
+    pub fn push(&mut self, element: T) -> Result<(), Error>
+    {
+        self.extend(std::slice::from_ref(&element))
+    }
+
+    pub fn extend(&mut self, data: &[T]) -> Result<(), Error>
+    {
+        let len = grown_len(self.len, data.len()).ok_or_else(|| Error::Misuse(String::from("GpuVec::extend: Too much data.")))?;
+        if len > self.view.count { self.reserve(grown_capacity(self.view.count, len))?; }
+        let buffer_ptr = self.buffer.allocation.as_ref().unwrap().mapped_ptr().unwrap().as_ptr();
+        unsafe
+        {
+            let buffer_ptr = buffer_ptr.add(self.view.offset_in_bytes + self.len as usize * self.view.stride as usize);
+            for (i, element) in data.iter().enumerate()
+            {
+                (buffer_ptr.add(i * self.view.stride as usize) as *mut T).write_unaligned(*element);
+            }
+        }
+        self.len = len;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn growth()
+    {
+        assert_eq!(grown_capacity(1, 2), 2);
+        assert_eq!(grown_capacity(4, 5), 8);
+        assert_eq!(grown_capacity(4, 100), 100);
+        assert_eq!(grown_capacity(u32::MAX / 2 + 1, u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn retired_per_frame()
+    {
+        let mut retired = std::collections::VecDeque::new();
+        //two frames in flight, buffers are retired while recording frames 0, 1, 0 and 1
+        retired.extend([(0, 'a'), (0, 'b'), (1, 'c')]);
+        free_retired(&mut retired, 0);
+        assert_eq!(retired, [(1, 'c')]);
+        retired.push_back((0, 'd'));
+        free_retired(&mut retired, 1);
+        assert_eq!(retired, [(0, 'd')]);
+        free_retired(&mut retired, 1);
+        assert_eq!(retired, [(0, 'd')]);
+        free_retired(&mut retired, 0);
+        assert!(retired.is_empty());
+    }
+
+    #[test]
+    fn generation_stales_descriptor_sets()
+    {
+        let current_generation = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let tracked = Arc::downgrade(&current_generation);
+        assert_eq!(crate::descriptor::buffer_error(&tracked, 0), None);
+        assert_eq!(next_generation(&current_generation, 0), 1);
+        assert!(crate::descriptor::buffer_error(&tracked, 0).is_some());
+        assert_eq!(crate::descriptor::buffer_error(&tracked, 1), None);
+        drop(current_generation);
+        assert!(crate::descriptor::buffer_error(&tracked, 1).is_some());
+    }
+
+    #[test]
+    fn length_overflow()
+    {
+        assert_eq!(grown_len(3, 4), Some(7));
+        assert_eq!(grown_len(u32::MAX - 1, 1), Some(u32::MAX));
+        assert_eq!(grown_len(u32::MAX, 1), None);
+    }
+}
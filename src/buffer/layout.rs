@@ -0,0 +1,300 @@
+use super::*;
+#[cfg(feature = "math")]
+use gru_misc::math::{Vec2, Vec3, Vec4, Mat2, Mat4};
+
+//compile time concatenation of the GLSL declarations in the layout derives
+#[doc(hidden)]
+pub const fn glsl_len(parts: &[&str]) -> usize
+{
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len()
+    {
+        len += parts[i].len();
+        i += 1;
+    }
+    len
+}
+
+//part is left out together with members of padding type
+#[doc(hidden)]
+pub const fn glsl_member(glsl_type: &'static str, part: &'static str) -> &'static str
+{
+    if glsl_type.is_empty() { "" } else { part }
+}
+
+#[doc(hidden)]
+pub const fn glsl_concat<const N: usize>(parts: &[&str]) -> [u8; N]
+{
+    let mut glsl = [0; N];
+    let mut offset = 0;
+    let mut i = 0;
+    while i < parts.len()
+    {
+        let bytes = parts[i].as_bytes();
+        let mut j = 0;
+        while j < bytes.len()
+        {
+            glsl[offset] = bytes[j];
+            offset += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    glsl
+}
+
+macro_rules! impl_layout
+{
+    ($($ty: ty => $align: expr, $size: expr, $glsl: expr;)+) =>
+    {
+        $(
+            unsafe impl Std140 for $ty
+            {
+                const ALIGN: usize = $align;
+                const SIZE: usize = $size;
+                const GLSL_TYPE: &'static str = $glsl;
+            }
+
+            unsafe impl Std430 for $ty
+            {
+                const ALIGN: usize = $align;
+                const SIZE: usize = $size;
+                const GLSL_TYPE: &'static str = $glsl;
+            }
+        )+
+    };
+    //std140 would round the alignment of these structs up to 16
+    (std430: $($ty: ident => $align: expr, $size: expr, $members: expr;)+) =>
+    {
+        $(
+            unsafe impl Std430 for $ty
+            {
+                const ALIGN: usize = $align;
+                const SIZE: usize = $size;
+                const GLSL_TYPE: &'static str = stringify!($ty);
+                const GLSL_STRUCT: &'static str = concat!("#ifndef GRU_VULKAN_STRUCT_", stringify!($ty), "\n#define GRU_VULKAN_STRUCT_", stringify!($ty), "\nstruct ", stringify!($ty), "\n{\n", $members, "};\n#endif\n");
+            }
+        )+
+    };
+}
+
+impl_layout!
+{
+    f32 => 4, 4, "float";
+    i32 => 4, 4, "int";
+    u32 => 4, 4, "uint";
+    F1 => 4, 4, "float";
+    I1 => 4, 4, "int";
+    U1 => 4, 4, "uint";
+    F2 => 8, 8, "vec2";
+    I2 => 8, 8, "ivec2";
+    U2 => 8, 8, "uvec2";
+    F3 => 16, 12, "vec3";
+    I3 => 16, 12, "ivec3";
+    U3 => 16, 12, "uvec3";
+    F4 => 16, 16, "vec4";
+    I4 => 16, 16, "ivec4";
+    U4 => 16, 16, "uvec4";
+    M3 => 16, 48, "mat3";
+    M3x4 => 16, 48, "mat3x4";
+    M4 => 16, 64, "mat4";
+}
+
+impl_layout!
+{
+    std430:
+    DrawIndirectCommand => 4, 16, "    uint vertex_count;\n    uint instance_count;\n    uint first_vertex;\n    uint first_instance;\n";
+    DrawIndexedIndirectCommand => 4, 20, "    uint index_count;\n    uint instance_count;\n    uint first_index;\n    int vertex_offset;\n    uint first_instance;\n";
+    DispatchIndirectCommand => 4, 12, "    uint x;\n    uint y;\n    uint z;\n";
+    IndirectCount => 4, 4, "    uint count;\n";
+}
+
+//Mat3 is left out, its three packed Vec3 columns take 36 bytes instead of the 48 of a GLSL mat3
+#[cfg(feature = "math")]
+impl_layout!
+{
+    Vec2 => 8, 8, "vec2";
+    Vec3 => 16, 12, "vec3";
+    Vec4 => 16, 16, "vec4";
+    Mat4 => 16, 64, "mat4";
+}
+
+#[cfg(feature = "math")]
+unsafe impl Std430 for Mat2
+{
+    const ALIGN: usize = 8;
+    const SIZE: usize = 16;
+    const GLSL_TYPE: &'static str = "mat2";
+}
+
+//the array stride is rounded up to 16 bytes, therefore [f32; N] is no vec
+unsafe impl<T: Std140, const N: usize> Std140 for [T; N]
+{
+    const ALIGN: usize = T::ALIGN.div_ceil(16) * 16;
+    const SIZE: usize = T::SIZE.div_ceil(Self::ALIGN) * Self::ALIGN * N;
+    //the derives put the array length after the member name
+    const GLSL_TYPE: &'static str = T::GLSL_TYPE;
+}
+
+unsafe impl<T: Std430, const N: usize> Std430 for [T; N]
+{
+    const ALIGN: usize = T::ALIGN;
+    const SIZE: usize = T::SIZE.div_ceil(Self::ALIGN) * Self::ALIGN * N;
+    const GLSL_TYPE: &'static str = T::GLSL_TYPE;
+}
+
+//std430 packs the columns of mat2 tightly, std140 pads them to vec4
+unsafe impl Std430 for M2
+{
+    const ALIGN: usize = 8;
+    const SIZE: usize = 16;
+    const GLSL_TYPE: &'static str = "mat2";
+}
+
+//explicit padding bytes to move the next field to its GLSL offset
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct Padding<const N: usize>([u8; N]);
+
+impl<const N: usize> Padding<N>
+{
+    pub const fn new() -> Self { Padding([0; N]) }
+}
+
+impl<const N: usize> Default for Padding<N>
+{
+    fn default() -> Self { Self::new() }
+}
+
+unsafe impl<const N: usize> Std140 for Padding<N>
+{
+    const ALIGN: usize = 1;
+    const SIZE: usize = N;
+    const GLSL_TYPE: &'static str = "";
+}
+
+unsafe impl<const N: usize> Std430 for Padding<N>
+{
+    const ALIGN: usize = 1;
+    const SIZE: usize = N;
+    const GLSL_TYPE: &'static str = "";
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::*;
+
+    #[derive(Clone, Copy, Std140, Std430)]
+    #[repr(C)]
+    struct Light
+    {
+        position: F3,
+        intensity: f32
+    }
+
+    #[derive(Clone, Copy, Std140)]
+    #[repr(C)]
+    #[glsl(set = 0, binding = 1, instance = "scene")]
+    struct Scene
+    {
+        view: M4,
+        lights: [Light; 2],
+        sun: Light,
+        count: u32,
+        _padding: Padding<12>
+    }
+
+    const LIGHT: &str = "#ifndef GRU_VULKAN_STRUCT_Light\n#define GRU_VULKAN_STRUCT_Light\nstruct Light\n{\n    vec3 position;\n    float intensity;\n};\n#endif\n";
+
+    #[test]
+    fn glsl_struct()
+    {
+        assert_eq!(<Light as Std140>::GLSL_STRUCT, LIGHT);
+        assert_eq!(<Light as Std430>::GLSL_STRUCT, LIGHT);
+        assert_eq!(<Light as Std430>::GLSL, "layout(std430) buffer Light\n{\n    vec3 position;\n    float intensity;\n};\n");
+    }
+
+    #[test]
+    fn glsl_block_with_nested_struct()
+    {
+        let block = "layout(set = 0, binding = 1, std140) uniform Scene\n{\n    mat4 view;\n    Light lights[2];\n    Light sun;\n    uint count;\n} scene;\n";
+        assert_eq!(<Scene as Std140>::GLSL, format!("{LIGHT}{block}"));
+        assert!(<Scene as Std140>::GLSL_STRUCT.starts_with(LIGHT));
+    }
+
+    #[derive(Clone, Copy, Std430)]
+    #[repr(C)]
+    struct Draws
+    {
+        count: IndirectCount,
+        commands: [DrawIndexedIndirectCommand; 4]
+    }
+
+    #[test]
+    fn glsl_indirect_commands()
+    {
+        let command = "#ifndef GRU_VULKAN_STRUCT_DrawIndexedIndirectCommand\n#define GRU_VULKAN_STRUCT_DrawIndexedIndirectCommand\nstruct DrawIndexedIndirectCommand\n{\n    uint index_count;\n    uint instance_count;\n    uint first_index;\n    int vertex_offset;\n    uint first_instance;\n};\n#endif\n";
+        assert_eq!(<DrawIndexedIndirectCommand as Std430>::GLSL_STRUCT, command);
+        assert!(<Draws as Std430>::GLSL.contains("struct IndirectCount\n{\n    uint count;\n};"));
+        assert!(<Draws as Std430>::GLSL.contains(command));
+        assert!(<Draws as Std430>::GLSL.ends_with("layout(std430) buffer Draws\n{\n    IndirectCount count;\n    DrawIndexedIndirectCommand commands[4];\n};\n"));
+        assert_eq!(<Draws as Std430>::SIZE, 84);
+    }
+
+    mod user
+    {
+        use crate::*;
+
+        //same name as the math type, but a struct in GLSL
+        #[derive(Clone, Copy, Std430)]
+        #[repr(C)]
+        pub struct Vec3
+        {
+            pub x: f32,
+            pub y: f32,
+            pub z: f32
+        }
+    }
+
+    #[derive(Clone, Copy, Std430)]
+    #[repr(C)]
+    struct Particle
+    {
+        position: user::Vec3,
+        weight: f32
+    }
+
+    #[test]
+    fn glsl_type_of_user_struct()
+    {
+        assert!(<Particle as Std430>::GLSL.starts_with("#ifndef GRU_VULKAN_STRUCT_Vec3\n"));
+        assert!(<Particle as Std430>::GLSL.ends_with("buffer Particle\n{\n    Vec3 position;\n    float weight;\n};\n"));
+    }
+
+    #[derive(Clone, Copy, VertexAttributeGroupReprCpacked)]
+    #[repr(C, packed)]
+    struct Vertex
+    {
+        #[location = 0]
+        position: F3,
+        #[location = 1]
+        color: Unorm8x4,
+        #[location = 2]
+        transform: M4
+    }
+
+    #[test]
+    fn glsl_attributes()
+    {
+        assert_eq!(<Vertex as AttributeGroupReprCpacked>::GLSL, "layout(location = 0) in vec3 position;\nlayout(location = 1) in vec4 color;\nlayout(location = 2) in mat4 transform;\n");
+    }
+
+    #[test]
+    fn layout_of_nested_struct()
+    {
+        assert_eq!(<Light as Std140>::SIZE, 16);
+        assert_eq!(<Scene as Std140>::SIZE, 64 + 2 * 16 + 16 + 16);
+    }
+}
@@ -1,5 +1,9 @@
 mod data;
 pub use data::*;
+mod layout;
+pub use layout::*;
+mod staging;
+mod gpu_vec;
 
 use super::*;
 
@@ -17,34 +21,37 @@ impl Device
             indices: false,
             attributes: false,
             uniforms: false,
-            storage: false
+            storage: false,
+            indirect: false
         };
         BufferTypeBuilder(buffer_type)
     }
 
-    pub fn new_buffer(&self, buffer_type: &BufferType, buffer_usage: BufferUsage) -> Buffer
+    pub fn new_buffer(&self, buffer_type: &BufferType, buffer_usage: BufferUsage) -> Result<Buffer, Error>
     {
-        if DEBUG_MODE && buffer_type.offset_in_bytes == 0 { panic!("Device::new_buffer: No empty buffers allowed."); }
+        if DEBUG_MODE && buffer_type.offset_in_bytes == 0 { return Err(Error::Misuse(String::from("Device::new_buffer: No empty buffers allowed."))); }
         let (location, mut buffer_usage_flags) = match buffer_usage
         {
             BufferUsage::Stage => (gpu_allocator::MemoryLocation::CpuToGpu, vk::BufferUsageFlags::TRANSFER_SRC),
             BufferUsage::Dynamic => (gpu_allocator::MemoryLocation::CpuToGpu, vk::BufferUsageFlags::empty()),
-            BufferUsage::Static => (gpu_allocator::MemoryLocation::GpuOnly, vk::BufferUsageFlags::TRANSFER_DST)
+            BufferUsage::Static => (gpu_allocator::MemoryLocation::GpuOnly, vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::TRANSFER_SRC),
+            BufferUsage::Readback => (gpu_allocator::MemoryLocation::GpuToCpu, vk::BufferUsageFlags::TRANSFER_DST)
         };
-        if buffer_usage != BufferUsage::Stage
+        if buffer_usage != BufferUsage::Stage && buffer_usage != BufferUsage::Readback
         {
             buffer_usage_flags |=
                 if buffer_type.indices { vk::BufferUsageFlags::INDEX_BUFFER } else { vk::BufferUsageFlags::empty() }
             | if buffer_type.attributes { vk::BufferUsageFlags::VERTEX_BUFFER } else { vk::BufferUsageFlags::empty() }
             | if buffer_type.uniforms { vk::BufferUsageFlags::UNIFORM_BUFFER } else { vk::BufferUsageFlags::empty() }
-            | if buffer_type.storage { vk::BufferUsageFlags::STORAGE_BUFFER } else { vk::BufferUsageFlags::empty() };
+            | if buffer_type.storage { vk::BufferUsageFlags::STORAGE_BUFFER } else { vk::BufferUsageFlags::empty() }
+            | if buffer_type.indirect { vk::BufferUsageFlags::INDIRECT_BUFFER } else { vk::BufferUsageFlags::empty() };
         }
         let buffer_create_info = vk::BufferCreateInfo::default()
             .size(buffer_type.offset_in_bytes)
             .usage(buffer_usage_flags);
 
         let device = &self.0.logical_device;
-        let buffer = unsafe { device.create_buffer(&buffer_create_info, None) }.unwrap();
+        let buffer = unsafe { device.create_buffer(&buffer_create_info, None) }?;
         let allocation_create_desc = alloc::AllocationCreateDesc
         {
             name: "",
@@ -53,18 +60,19 @@ impl Device
             linear: true,
             allocation_scheme: alloc::AllocationScheme::GpuAllocatorManaged
         };
-        let allocation = self.0.allocator.as_ref().unwrap().lock().unwrap().allocate(&allocation_create_desc).unwrap();
-        unsafe { device.bind_buffer_memory(buffer, allocation.memory(), allocation.offset()).unwrap(); }
+        let allocation = self.0.allocate_buffer_memory(buffer, &allocation_create_desc)?;
         
-        Buffer
+        Ok(Buffer
         {
             device: self.0.clone(),
             allocation: Some(allocation),
             buffer,
             buffer_usage,
             layout_id: buffer_type.id,
-            size_in_bytes: buffer_type.offset_in_bytes
-        }
+            size_in_bytes: buffer_type.offset_in_bytes,
+            generation: 0,
+            current_generation: Arc::new(std::sync::atomic::AtomicU32::new(0))
+        })
     }
 }
 
@@ -75,7 +83,8 @@ impl AttributeGroupInfo
         Self
         {
             rate: T::RATE,
-            attributes: T::ATTRIBUTES
+            attributes: T::ATTRIBUTES,
+            stride: T::STRIDE
         }
     }
 }
@@ -98,6 +107,13 @@ impl BufferTypeBuilder
     pub fn add_attributes_storage<T: AttributeGroupReprCpacked + StorageStructReprC>(&mut self, count: u32) -> BufferView<T> { self.add_attributes_internal(count, true) }
     pub fn add_uniforms_storage<T: DescriptorStructReprC + StorageStructReprC>(&mut self, count: u32) -> BufferView<T> { self.add_uniforms_internal(count, true) }
     pub fn add_storage<T: StorageStructReprC>(&mut self, count: u32) -> BufferView<T> { self.add(count, 1, 1, true) } //add does offset_alignment
+    //every element starts at a valid dynamic offset, for DescriptorBindingType::DynamicStorage
+    pub fn add_dynamic_storage<T: StorageStructReprC>(&mut self, count: u32) -> BufferView<T> { self.add(count, 1, self.0.storage_align, true) }
+    //indirect commands are also storage, so compute shaders can write them
+    pub fn add_indirect_draws(&mut self, count: u32) -> BufferView<DrawIndirectCommand> { self.add_indirect_internal(count) }
+    pub fn add_indirect_indexed_draws(&mut self, count: u32) -> BufferView<DrawIndexedIndirectCommand> { self.add_indirect_internal(count) }
+    pub fn add_indirect_dispatches(&mut self, count: u32) -> BufferView<DispatchIndirectCommand> { self.add_indirect_internal(count) }
+    pub fn add_indirect_counts(&mut self, count: u32) -> BufferView<IndirectCount> { self.add_indirect_internal(count) }
 
     fn ggt(mut a: u64, mut b: u64) -> u64
     {
@@ -133,6 +149,12 @@ impl BufferTypeBuilder
         self.add(count, self.0.uniform_align, self.0.uniform_align, storage) //stride_align because multiple uniforms -> uniform array with offset_align for everyone
     }
 
+    fn add_indirect_internal<T: StorageStructReprC>(&mut self, count: u32) -> BufferView<T>
+    {
+        self.0.indirect = true;
+        self.add(count, 4, 1, true)
+    }
+
     fn add<T>(&mut self, count: u32, mut offset_align: u64, stride_align: u64, storage: bool) -> BufferView<T>
     {
         if storage
@@ -167,7 +189,8 @@ pub enum BufferUsage
 {
     Stage,
     Dynamic,
-    Static
+    Static,
+    Readback //copy target of Static buffers, read on the CPU
 }
 //gpu_allocator does persistent mapping, but we keep this BufferMap API around for a potential swap to gpu_alloc
 impl Buffer
@@ -225,11 +248,49 @@ impl<'a> BufferMap<'a>
         self.write(view, offset, data);
     }
 
+    #[inline]
+    pub fn read_indices<T: IndexType + Copy>(&self, view: &BufferView<T>, offset: usize, data: &mut [T])
+    {
+        self.check(view, offset, data.len());
+        self.read(view, offset, data);
+    }
+
+    #[inline]
+    pub fn read_attributes<T: AttributeGroupReprCpacked + Copy>(&self, view: &BufferView<T>, offset: usize, data: &mut [T])
+    {
+        self.check(view, offset, data.len());
+        self.read(view, offset, data);
+    }
+
+    #[inline]
+    pub fn read_uniforms<T: DescriptorStructReprC>(&self, view: &BufferView<T>, offset: usize, data: &mut [T])
+    {
+        self.check(view, offset, data.len());
+        self.read(view, offset, data);
+    }
+
+    #[inline]
+    pub fn read_storage<T: StorageStructReprC + Copy>(&self, view: &BufferView<T>, offset: usize, data: &mut [T])
+    {
+        self.check(view, offset, data.len());
+        self.read(view, offset, data);
+    }
+
     #[inline(always)]
     fn check<T>(&self, view: &BufferView<T>, offset: usize, count: usize)
     {
-        if DEBUG_MODE && view.layout_id != self.buffer.layout_id { panic!("BufferMap_write_?: Incompatible BufferView."); }
-        if offset + count > view.count as usize { panic!("BufferMap_write_?: Too much data."); } //TODO only in DEBUG_MODE?
+        if DEBUG_MODE && view.layout_id != self.buffer.layout_id { panic!("BufferMap_?: Incompatible BufferView."); }
+        if offset + count > view.count as usize { panic!("BufferMap_?: Too much data."); } //TODO only in DEBUG_MODE?
+    }
+
+    #[inline(always)]
+    fn read<T: Copy>(&self, view: &BufferView<T>, offset: usize, data: &mut [T])
+    {
+        unsafe
+        {
+            let buffer_ptr = self.buffer_ptr.add(view.offset_in_bytes + offset * view.stride as usize) as *const u8;
+            read_strided(buffer_ptr, view.stride as usize, data);
+        }
     }
 
     #[inline(always)]
@@ -237,12 +298,32 @@ impl<'a> BufferMap<'a>
     {
         unsafe
         {
-            let buffer_ptr = (self.buffer_ptr.add(view.offset_in_bytes) as *mut T).add(offset);
-            buffer_ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
+            let buffer_ptr = self.buffer_ptr.add(view.offset_in_bytes + offset * view.stride as usize) as *mut u8;
+            write_strided(buffer_ptr, view.stride as usize, data);
         }
     }
 }
 
+//the elements are placed stride bytes apart, e.g. padded to the alignment of dynamic offsets
+#[inline(always)]
+unsafe fn write_strided<T>(dst: *mut u8, stride: usize, data: &[T])
+{
+    for (i, element) in data.iter().enumerate()
+    {
+        unsafe { dst.add(i * stride).copy_from_nonoverlapping(element as *const T as *const u8, std::mem::size_of::<T>()); }
+    }
+}
+
+//the counterpart of write_strided, uniforms and dynamic storage are padded
+#[inline(always)]
+unsafe fn read_strided<T: Copy>(src: *const u8, stride: usize, data: &mut [T])
+{
+    for (i, element) in data.iter_mut().enumerate()
+    {
+        *element = unsafe { (src.add(i * stride) as *const T).read_unaligned() };
+    }
+}
+
 /* Unmap Buffer here if not persistent mapped.
 impl Drop for BufferMap<'_>
 {
@@ -259,8 +340,9 @@ impl CommandBuffer
     {
         if DEBUG_MODE && self.pool.queue_family_index != queue.index { panic!("CommandBuffer::copy_buffer: Wrong queue family."); }
         if DEBUG_MODE && !self.pool.queue_family_flags.contains(vk::QueueFlags::TRANSFER) { panic!("CommandBuffer::copy_buffer: This queue family does not support transfer operations."); }
-        if DEBUG_MODE && src.buffer_usage != BufferUsage::Stage { panic!("CommandBuffer::copy_buffer: Source buffer has not stage memory type."); }
-        if DEBUG_MODE && dst.buffer_usage != BufferUsage::Static { panic!("CommandBuffer::copy_buffer: Destination buffer has not static memory type."); }
+        let readback = dst.buffer_usage == BufferUsage::Readback;
+        if DEBUG_MODE && src.buffer_usage != if readback { BufferUsage::Static } else { BufferUsage::Stage } { panic!("CommandBuffer::copy_buffer: Source buffer has not stage memory type (static for readback)."); }
+        if DEBUG_MODE && !readback && dst.buffer_usage != BufferUsage::Static { panic!("CommandBuffer::copy_buffer: Destination buffer has not static or readback memory type."); }
         if DEBUG_MODE && src.layout_id != dst.layout_id { panic!("CommandBuffer::copy_buffer: Buffer need to have the same layout."); }
 
         let command_buffer_begin_info = vk::CommandBufferBeginInfo::default()
@@ -278,7 +360,22 @@ impl CommandBuffer
         unsafe
         {
             self.pool.device.logical_device.begin_command_buffer(self.command_buffer, &command_buffer_begin_info).unwrap();
+            //everything written before must be visible to the copy, and the copy to the host
+            if readback
+            {
+                let memory_barrier = vk::MemoryBarrier::default()
+                    .src_access_mask(vk::AccessFlags::MEMORY_WRITE)
+                    .dst_access_mask(vk::AccessFlags::TRANSFER_READ);
+                self.pool.device.logical_device.cmd_pipeline_barrier(self.command_buffer, vk::PipelineStageFlags::ALL_COMMANDS, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[memory_barrier], &[], &[]);
+            }
             self.pool.device.logical_device.cmd_copy_buffer(self.command_buffer, src.buffer, dst.buffer, &[buffer_copy]);
+            if readback
+            {
+                let memory_barrier = vk::MemoryBarrier::default()
+                    .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                    .dst_access_mask(vk::AccessFlags::HOST_READ);
+                self.pool.device.logical_device.cmd_pipeline_barrier(self.command_buffer, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::HOST, vk::DependencyFlags::empty(), &[memory_barrier], &[], &[]);
+            }
             self.pool.device.logical_device.end_command_buffer(self.command_buffer).unwrap();
             self.pool.device.logical_device.queue_submit(queue.queue, &submit_info, mark.fence).unwrap();
         }
@@ -288,15 +385,18 @@ impl CommandBuffer
 
 impl<'a> CommandBufferRecord<'a>
 {
+    //for CopyViewUsage::Readback, stage is the one that wrote the source view
     pub fn copy_view<T>(&self, src_buf: &Buffer, src_view: &BufferView<T>, dst_buf: &Buffer, dst_view: &BufferView<T>, usage: CopyViewUsage, stage: WaitStage)
     {
-        if DEBUG_MODE && src_buf.buffer_usage != BufferUsage::Stage { panic!("CommandBuffer::copy_view: Source buffer has not stage memory type."); }
-        if DEBUG_MODE && dst_buf.buffer_usage != BufferUsage::Static { panic!("CommandBuffer::copy_view: Destination buffer has not static memory type."); }
+        let readback = dst_buf.buffer_usage == BufferUsage::Readback;
+        if DEBUG_MODE && src_buf.buffer_usage != if readback { BufferUsage::Static } else { BufferUsage::Stage } { panic!("CommandBuffer::copy_view: Source buffer has not stage memory type (static for readback)."); }
+        if DEBUG_MODE && !readback && dst_buf.buffer_usage != BufferUsage::Static { panic!("CommandBuffer::copy_view: Destination buffer has not static or readback memory type."); }
+        if DEBUG_MODE && readback != matches!(usage, CopyViewUsage::Readback) { panic!("CommandBuffer::copy_view: CopyViewUsage::Readback is for readback buffers only."); }
         if DEBUG_MODE && src_buf.layout_id != src_view.layout_id { panic!("CommandBuffer::copy_view: Source buffer and view are not compatible."); }
         if DEBUG_MODE && dst_buf.layout_id != dst_view.layout_id { panic!("CommandBuffer::copy_view: Destination buffer and view are not compatible."); }
         if DEBUG_MODE && src_view.count != dst_view.count { panic!("CommandBuffer::copy_view: Source and destination views have different counts."); }
 
-        let size = std::mem::size_of::<T>() as u64 * src_view.count as u64;
+        let size = src_view.stride as u64 * src_view.count as u64;
         let buffer_copy = vk::BufferCopy
         {
             src_offset: src_view.offset_in_bytes as u64,
@@ -311,10 +411,23 @@ impl<'a> CommandBufferRecord<'a>
             .buffer(dst_buf.buffer)
             .offset(dst_view.offset_in_bytes as u64)
             .size(size);
+        let dst_stage = if readback { vk::PipelineStageFlags::HOST } else { stage.vk_mask() };
         unsafe
         {
+            if readback && !matches!(stage, WaitStage::None)
+            {
+                let write_barrier = vk::BufferMemoryBarrier::default()
+                    .src_access_mask(vk::AccessFlags::SHADER_WRITE)
+                    .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
+                    .src_queue_family_index(self.buffer.pool.queue_family_index as u32)
+                    .dst_queue_family_index(self.buffer.pool.queue_family_index as u32)
+                    .buffer(src_buf.buffer)
+                    .offset(src_view.offset_in_bytes as u64)
+                    .size(size);
+                self.buffer.pool.device.logical_device.cmd_pipeline_barrier(self.buffer.command_buffer, stage.vk_mask(), vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[write_barrier], &[]);
+            }
             self.buffer.pool.device.logical_device.cmd_copy_buffer(self.buffer.command_buffer, src_buf.buffer, dst_buf.buffer, &[buffer_copy]);
-            self.buffer.pool.device.logical_device.cmd_pipeline_barrier(self.buffer.command_buffer, vk::PipelineStageFlags::TRANSFER, stage.vk_mask(), vk::DependencyFlags::empty(), &[], &[memory_barrier], &[]);
+            self.buffer.pool.device.logical_device.cmd_pipeline_barrier(self.buffer.command_buffer, vk::PipelineStageFlags::TRANSFER, dst_stage, vk::DependencyFlags::empty(), &[], &[memory_barrier], &[]);
         }
     }
 }
@@ -322,7 +435,13 @@ impl<'a> CommandBufferRecord<'a>
 #[derive(Clone, Copy)]
 pub enum CopyViewUsage
 {
-    Uniform
+    Uniform,
+    Vertex,
+    Index,
+    Indirect,
+    //read by shaders as storage buffer
+    Storage,
+    Readback
 }
 
 impl CopyViewUsage
@@ -331,7 +450,12 @@ impl CopyViewUsage
     {
         match self
         {
-            Self::Uniform => vk::AccessFlags::UNIFORM_READ
+            Self::Uniform => vk::AccessFlags::UNIFORM_READ,
+            Self::Vertex => vk::AccessFlags::VERTEX_ATTRIBUTE_READ,
+            Self::Index => vk::AccessFlags::INDEX_READ,
+            Self::Indirect => vk::AccessFlags::INDIRECT_COMMAND_READ,
+            Self::Storage => vk::AccessFlags::SHADER_READ,
+            Self::Readback => vk::AccessFlags::HOST_READ
         }
     }
 }
@@ -364,3 +488,87 @@ impl<'a> AttributeBinding<'a>
         }
     }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn builder(uniform_align: u64, storage_align: u64) -> BufferTypeBuilder
+    {
+        BufferTypeBuilder(BufferType { id: 0, offset_in_bytes: 0, uniform_align, storage_align, indices: false, attributes: false, uniforms: false, storage: false, indirect: false })
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Element([f32; 5]);
+    impl StorageStructReprC for Element { }
+
+    #[test]
+    fn storage_offsets()
+    {
+        let mut builder = builder(256, 64);
+        let indices = builder.add_indices::<u16>(3);
+        let storage = builder.add_storage::<Element>(3);
+        assert_eq!((indices.offset_in_bytes, indices.stride), (0, 2));
+        //the offset is aligned, the elements are packed
+        assert_eq!((storage.offset_in_bytes, storage.stride), (64, 20));
+        assert_eq!(builder.0.offset_in_bytes, 64 + 3 * 20);
+    }
+
+    #[test]
+    fn dynamic_storage_stride()
+    {
+        let mut builder = builder(256, 64);
+        let _ = builder.add_indices::<u32>(1);
+        let view = builder.add_dynamic_storage::<Element>(3);
+        assert_eq!((view.offset_in_bytes, view.stride), (64, 64));
+        assert_eq!(builder.0.offset_in_bytes, 64 + 3 * 64);
+        assert!(builder.0.storage);
+    }
+
+    #[test]
+    fn dynamic_storage_write()
+    {
+        let mut builder = builder(256, 64);
+        let _ = builder.add_indices::<u32>(1);
+        let view = builder.add_dynamic_storage::<Element>(2);
+        let mut bytes = vec![0u8; builder.0.offset_in_bytes as usize];
+        let data = [Element([1.0; 5]), Element([2.0; 5])];
+        unsafe { write_strided(bytes.as_mut_ptr().add(view.offset_in_bytes), view.stride as usize, &data); }
+        //every element is where its dynamic offset points the shader at, relative to the bound view
+        for (index, value) in [(0, 1.0f32), (1, 2.0)]
+        {
+            let offset = view.offset_in_bytes + DynamicOffset::new(&view, index).offset_in_bytes as usize;
+            assert_eq!(&bytes[offset..offset + 4], &value.to_ne_bytes());
+            assert_eq!(&bytes[offset + 16..offset + 20], &value.to_ne_bytes());
+        }
+        assert_eq!(&bytes[64 + 20..128], &[0; 44]);
+    }
+
+    #[test]
+    fn dynamic_storage_read()
+    {
+        let mut builder = builder(256, 64);
+        let view = builder.add_dynamic_storage::<Element>(3);
+        let mut bytes = vec![0u8; builder.0.offset_in_bytes as usize];
+        for (i, value) in [1.0f32, 2.0, 3.0].into_iter().enumerate() { bytes[i * 64..i * 64 + 4].copy_from_slice(&value.to_ne_bytes()); }
+        let mut data = [Element([0.0; 5]); 2];
+        unsafe { read_strided(bytes.as_ptr().add(view.offset_in_bytes + view.stride as usize), view.stride as usize, &mut data); }
+        assert_eq!([data[0].0[0], data[1].0[0]], [2.0, 3.0]);
+    }
+
+    #[test]
+    fn uniform_stride()
+    {
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        struct Uniform([f32; 4]);
+        impl DescriptorStructReprC for Uniform { }
+
+        let mut builder = builder(256, 64);
+        let _ = builder.add_storage::<Element>(1);
+        let view = builder.add_uniforms::<Uniform>(2);
+        assert_eq!((view.offset_in_bytes, view.stride), (256, 256));
+    }
+}
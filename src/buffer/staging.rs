@@ -0,0 +1,170 @@
+use super::*;
+
+impl Device
+{
+    pub fn new_staging_ring(&self, size_in_mb: u32) -> Result<StagingRing, Error>
+    {
+        if DEBUG_MODE && size_in_mb == 0 { return Err(Error::Misuse(String::from("Device::new_staging_ring: No empty rings allowed."))); }
+        let size_in_bytes = size_in_mb as u64 * 1024 * 1024;
+        let buffer_create_info = vk::BufferCreateInfo::default()
+            .size(size_in_bytes)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC);
+
+        let device = &self.0.logical_device;
+        let buffer = unsafe { device.create_buffer(&buffer_create_info, None) }?;
+        let allocation_create_desc = alloc::AllocationCreateDesc
+        {
+            name: "",
+            requirements: unsafe { device.get_buffer_memory_requirements(buffer) },
+            location: gpu_allocator::MemoryLocation::CpuToGpu,
+            linear: true,
+            allocation_scheme: alloc::AllocationScheme::GpuAllocatorManaged
+        };
+        let allocation = self.0.allocate_buffer_memory(buffer, &allocation_create_desc)?;
+
+        Ok(StagingRing
+        {
+            device: self.0.clone(),
+            allocation: Some(allocation),
+            buffer,
+            size_in_bytes,
+            head: 0,
+            tail: 0,
+            frame: 0,
+            in_flight: std::collections::VecDeque::new()
+        })
+    }
+}
+
+//the position of the next allocation, None if the ring is full
+fn ring_allocate(head: u64, tail: u64, ring_size: u64, size_in_bytes: u64, align: u64) -> Option<u64>
+{
+    let mut position = head.div_ceil(align) * align;
+    //an allocation never wraps around the end of the buffer
+    if position % ring_size + size_in_bytes > ring_size { position = (position / ring_size + 1) * ring_size; }
+    if position + size_in_bytes - tail > ring_size { return None; }
+    Some(position)
+}
+
+impl StagingRing
+{
+    //the offset the data is staged at
+    fn allocate(&mut self, size_in_bytes: u64, align: u64) -> Result<u64, Error>
+    {
+        let position = ring_allocate(self.head, self.tail, self.size_in_bytes, size_in_bytes, align)
+            .ok_or_else(|| Error::Misuse(String::from("StagingRing::upload: The ring is full, call StagingRing::begin_frame every frame or enlarge it.")))?;
+        self.head = position + size_in_bytes;
+        Ok(position % self.size_in_bytes)
+    }
+
+    //call with the index of the frame in flight (FrameRecord::frame) once its fence has been waited for,
+    //this frees the uploads recorded the last time the frame was used and all earlier ones
+    pub fn begin_frame(&mut self, frame: usize)
+    {
+        if self.in_flight.back().map_or(self.tail, |&(_, end)| end) != self.head { self.in_flight.push_back((self.frame, self.head)); }
+        if let Some(last) = self.in_flight.iter().rposition(|&(in_flight, _)| in_flight == frame)
+        {
+            self.tail = self.in_flight[last].1;
+            self.in_flight.drain(..=last);
+        }
+        self.frame = frame;
+    }
+
+    //stages data at the returned offset in the ring
+    fn stage<T: Copy>(&mut self, data: &[T], stride: usize, align: u64) -> Result<u64, Error>
+    {
+        let size = (stride * data.len()) as u64;
+        if size > self.size_in_bytes { return Err(Error::Misuse(String::from("StagingRing::upload: The data is larger than the ring."))); }
+        let src_offset = self.allocate(size, align)?;
+        unsafe
+        {
+            let ring_ptr = self.allocation.as_ref().unwrap().mapped_ptr().unwrap().as_ptr().add(src_offset as usize);
+            for (i, element) in data.iter().enumerate()
+            {
+                (ring_ptr.add(i * stride) as *mut T).write_unaligned(*element);
+            }
+        }
+        Ok(src_offset)
+    }
+
+    pub fn upload<T: Copy>(&mut self, record: &CommandBufferRecord, dst_buf: &Buffer, dst_view: &BufferView<T>, data: &[T], info: UploadInfo) -> Result<(), Error>
+    {
+        let UploadInfo { offset, usage, stage } = info;
+        if DEBUG_MODE && dst_buf.buffer_usage != BufferUsage::Static { panic!("StagingRing::upload: Destination buffer has not static memory type."); }
+        if DEBUG_MODE && dst_buf.layout_id != dst_view.layout_id { panic!("StagingRing::upload: Destination buffer and view are not compatible."); }
+        if DEBUG_MODE && matches!(usage, CopyViewUsage::Readback) { panic!("StagingRing::upload: Uploads cannot be read back."); }
+        if offset + data.len() > dst_view.count as usize { return Err(Error::Misuse(String::from("StagingRing::upload: Too much data."))); }
+        if data.is_empty() { return Ok(()); }
+
+        //the data is staged with the stride of the view, so one copy suffices, 16 bytes are enough for every type we copy
+        let size = dst_view.stride as u64 * data.len() as u64;
+        let src_offset = self.stage(data, dst_view.stride as usize, 16)?;
+
+        let dst_offset = (dst_view.offset_in_bytes + offset * dst_view.stride as usize) as u64;
+        let buffer_copy = vk::BufferCopy
+        {
+            src_offset,
+            dst_offset,
+            size
+        };
+        let memory_barrier = vk::BufferMemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(usage.vk_access_flags())
+            .src_queue_family_index(record.buffer.pool.queue_family_index as u32)
+            .dst_queue_family_index(record.buffer.pool.queue_family_index as u32)
+            .buffer(dst_buf.buffer)
+            .offset(dst_offset)
+            .size(size);
+        unsafe
+        {
+            record.buffer.pool.device.logical_device.cmd_copy_buffer(record.buffer.command_buffer, self.buffer, dst_buf.buffer, &[buffer_copy]);
+            record.buffer.pool.device.logical_device.cmd_pipeline_barrier(record.buffer.command_buffer, vk::PipelineStageFlags::TRANSFER, stage.vk_mask(), vk::DependencyFlags::empty(), &[], &[memory_barrier], &[]);
+        }
+        Ok(())
+    }
+
+    //one layer of a texture, the mipmaps are generated and the image is left ready for sampling in the fragment shader
+    pub fn upload_image(&mut self, record: &CommandBufferRecord, dst: &Image, layer: u32, data: &[u8]) -> Result<(), Error>
+    {
+        if DEBUG_MODE && matches!(dst.image_usage, ImageUsage::Attachment { .. }) { panic!("StagingRing::upload_image: Cannot transfer to framebuffer."); }
+        if DEBUG_MODE && layer >= dst.image_type.layers() { panic!("StagingRing::upload_image: Layer too large."); }
+        if data.len() as u64 != dst.image_type.layer_size_in_bytes() { return Err(Error::Misuse(String::from("StagingRing::upload_image: Incompatible data size."))); }
+        //the buffer offset must be a multiple of the texel size
+        let align = (dst.image_type.channel.size_in_bytes() as u64).max(16);
+        let src_offset = self.stage(data, 1, align)?;
+        record_image_upload(&record.buffer.pool.device, record.buffer.command_buffer, self.buffer, src_offset, dst, layer);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn allocate_aligned()
+    {
+        assert_eq!(ring_allocate(0, 0, 256, 100, 16), Some(0));
+        assert_eq!(ring_allocate(100, 0, 256, 100, 16), Some(112));
+        assert_eq!(ring_allocate(100, 0, 256, 8, 32), Some(128));
+    }
+
+    #[test]
+    fn allocate_wraps()
+    {
+        //the allocation does not fit before the end, it starts at the beginning of the next lap
+        assert_eq!(ring_allocate(200, 150, 256, 100, 16), Some(256));
+        assert_eq!(ring_allocate(200, 50, 256, 100, 16), None);
+        assert_eq!(ring_allocate(300, 280, 256, 200, 16), Some(304));
+        assert_eq!(ring_allocate(300, 280, 256, 240, 16), None);
+    }
+
+    #[test]
+    fn allocate_full()
+    {
+        assert_eq!(ring_allocate(0, 0, 256, 256, 16), Some(0));
+        assert_eq!(ring_allocate(256, 0, 256, 16, 16), None);
+        assert_eq!(ring_allocate(256, 16, 256, 16, 16), Some(256));
+    }
+}
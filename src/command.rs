@@ -2,26 +2,26 @@ use super::*;
 
 impl Device
 {
-    pub fn new_command_pool(&self, queue_family: &QueueFamily) -> CommandPool
+    pub fn new_command_pool(&self, queue_family: &QueueFamily) -> Result<CommandPool, Error>
     {
         let command_pool_info = vk::CommandPoolCreateInfo::default()
             .queue_family_index(queue_family.index as u32)
             .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER);
-        let command_pool = unsafe { self.0.logical_device.create_command_pool(&command_pool_info, None) }.unwrap();
+        let command_pool = unsafe { self.0.logical_device.create_command_pool(&command_pool_info, None) }?;
         let raw_pool = RawCommandPool { device: self.0.clone(), pool: command_pool, queue_family_index: queue_family.index, queue_family_flags: queue_family.flags };
-        CommandPool { pool: Rc::new(raw_pool) }
+        Ok(CommandPool { pool: Rc::new(raw_pool) })
     }
 }
 
 impl CommandPool
 {
-    pub fn new_command_buffer(&self) -> CommandBuffer
+    pub fn new_command_buffer(&self) -> Result<CommandBuffer, Error>
     {
         let command_bufffer_allocate_info = vk::CommandBufferAllocateInfo::default()
             .command_pool(self.pool.pool)
             .command_buffer_count(1);
-        let command_buffer = unsafe { self.pool.device.logical_device.allocate_command_buffers(&command_bufffer_allocate_info) }.unwrap()[0];
-        CommandBuffer { pool: Rc::clone(&self.pool), command_buffer }
+        let command_buffer = unsafe { self.pool.device.logical_device.allocate_command_buffers(&command_bufffer_allocate_info) }?[0];
+        Ok(CommandBuffer { pool: Rc::clone(&self.pool), command_buffer })
     }
 }
 
@@ -67,9 +67,9 @@ impl CommandBuffer
     }
 
     #[inline]
-    pub fn submit<const N: usize, const M: usize>(&self, queue: &Queue, wait: [&Semaphore; N], signal: [&Semaphore; M], mark: Option<&Fence>)
+    pub fn submit<const N: usize, const M: usize>(&self, queue: &Queue, wait: [&Semaphore; N], signal: [&Semaphore; M], mark: Option<&Fence>) -> Result<(), Error>
     {
-        if DEBUG_MODE && self.pool.queue_family_index != queue.index { panic!("CommandBuffer::submit: Wrong queue family."); }
+        if DEBUG_MODE && self.pool.queue_family_index != queue.index { return Err(Error::Misuse(String::from("CommandBuffer::submit: Wrong queue family."))); }
         let mut submit_info = vk::SubmitInfo::default()
             .command_buffers(std::slice::from_ref(&self.command_buffer));
         let wait_semaphores = wait.map(|wait| wait.semaphore);
@@ -87,7 +87,9 @@ impl CommandBuffer
         }
         let submit_info = [submit_info];
         let fence = mark.map(|mark| mark.fence).unwrap_or(vk::Fence::null());
-        unsafe { self.pool.device.logical_device.queue_submit(queue.queue, &submit_info, fence) }.unwrap();
+        unsafe { self.pool.device.logical_device.queue_submit(queue.queue, &submit_info, fence) }?;
+        self.pool.device.instance.0.raise_debug_error();
+        Ok(())
     }
 }
 
@@ -106,10 +108,25 @@ impl<'a> CommandBufferRecord<'a>
     }
 
     #[inline]
-    fn bind_descriptor_sets_internal(&mut self, pipeline_layout: &PipelineLayout, descriptor_sets: &[&DescriptorSet], bind_point: vk::PipelineBindPoint) -> &mut Self
+    fn bind_descriptor_sets_internal(&mut self, pipeline_layout: &PipelineLayout, descriptor_sets: &[&DescriptorSet], dynamic_offsets: &[DynamicOffset], bind_point: vk::PipelineBindPoint) -> &mut Self
     {
+        let mut dynamic_offsets = dynamic_offsets;
         for set in descriptor_sets
         {
+            //every set takes as many offsets as it has dynamic bindings, a wrong amount is undefined behavior
+            let dynamic_count = set.layout.dynamic_count();
+            if dynamic_offsets.len() < dynamic_count { panic!("CommandBufferRecord::bind_descriptor_sets_dynamic: Too few dynamic offsets."); }
+            let (set_offsets, rest) = dynamic_offsets.split_at(dynamic_count);
+            dynamic_offsets = rest;
+            if DEBUG_MODE
+            {
+                set.check_buffers();
+                set.check_dynamic_offsets(set_offsets);
+            }
+            //new_descriptor_set_layout limits the dynamic bindings, so the offsets fit on the stack
+            let mut offsets = [0; MAX_DYNAMIC_BINDINGS];
+            for (offset, set_offset) in offsets.iter_mut().zip(set_offsets) { *offset = set_offset.offset_in_bytes; }
+            let set_offsets = &offsets[..dynamic_count];
             unsafe
             {
                 self.buffer.pool.device.logical_device.cmd_bind_descriptor_sets
@@ -119,17 +136,25 @@ impl<'a> CommandBufferRecord<'a>
                     pipeline_layout.layout,
                     set.layout.set,
                     &[set.descriptor_set],
-                    &[]
+                    set_offsets
                 );
             }
         }
+        if !dynamic_offsets.is_empty() { panic!("CommandBufferRecord::bind_descriptor_sets_dynamic: Too many dynamic offsets."); }
         self
     }
 
     #[inline]
     pub fn bind_descriptor_sets(&mut self, pipeline_layout: &PipelineLayout, descriptor_sets: &[&DescriptorSet]) -> &mut Self
     {
-        self.bind_descriptor_sets_internal(pipeline_layout, descriptor_sets, vk::PipelineBindPoint::COMPUTE)
+        self.bind_descriptor_sets_internal(pipeline_layout, descriptor_sets, &[], vk::PipelineBindPoint::COMPUTE)
+    }
+
+    //one offset per dynamic binding, in set and binding order
+    #[inline]
+    pub fn bind_descriptor_sets_dynamic(&mut self, pipeline_layout: &PipelineLayout, descriptor_sets: &[&DescriptorSet], dynamic_offsets: &[DynamicOffset]) -> &mut Self
+    {
+        self.bind_descriptor_sets_internal(pipeline_layout, descriptor_sets, dynamic_offsets, vk::PipelineBindPoint::COMPUTE)
     }
 
     #[inline]
@@ -152,6 +177,17 @@ impl<'a> CommandBufferRecord<'a>
         self
     }
 
+    //dispatches the command at index of the view
+    #[inline]
+    pub fn dispatch_indirect(&mut self, buffer: &Buffer, view: &BufferView<DispatchIndirectCommand>, index: u32) -> &mut Self
+    {
+        if DEBUG_MODE && buffer.layout_id != view.layout_id { panic!("CommandBufferRecord::dispatch_indirect: Incompatible BufferView."); }
+        if DEBUG_MODE && index >= view.count { panic!("CommandBufferRecord::dispatch_indirect: Index out of bounds."); }
+        let offset_in_bytes = view.offset_in_bytes as u64 + index as u64 * view.stride as u64;
+        unsafe { self.buffer.pool.device.logical_device.cmd_dispatch_indirect(self.buffer.command_buffer, buffer.buffer, offset_in_bytes); }
+        self
+    }
+
     #[inline]
     pub fn render_pass<'b>(&'b mut self, render_pass: &RenderPass, framebuffer: &Framebuffer) -> CommandBufferRecordRenderPass<'a, 'b>
     {
@@ -271,7 +307,15 @@ impl<'a, 'b> CommandBufferRecordRenderPass<'a, 'b>
     #[inline]
     pub fn bind_descriptor_sets(&mut self, pipeline_layout: &PipelineLayout, descriptor_sets: &[&DescriptorSet]) -> &mut Self
     {
-        self.record.bind_descriptor_sets_internal(pipeline_layout, descriptor_sets, vk::PipelineBindPoint::GRAPHICS);
+        self.record.bind_descriptor_sets_internal(pipeline_layout, descriptor_sets, &[], vk::PipelineBindPoint::GRAPHICS);
+        self
+    }
+
+    //one offset per dynamic binding, in set and binding order
+    #[inline]
+    pub fn bind_descriptor_sets_dynamic(&mut self, pipeline_layout: &PipelineLayout, descriptor_sets: &[&DescriptorSet], dynamic_offsets: &[DynamicOffset]) -> &mut Self
+    {
+        self.record.bind_descriptor_sets_internal(pipeline_layout, descriptor_sets, dynamic_offsets, vk::PipelineBindPoint::GRAPHICS);
         self
     }
 
@@ -298,6 +342,58 @@ impl<'a, 'b> CommandBufferRecordRenderPass<'a, 'b>
         };
         self
     }
+
+    #[inline(always)]
+    fn check_indirect<T>(&self, name: &str, buffer: &Buffer, view: &BufferView<T>)
+    {
+        if DEBUG_MODE && buffer.layout_id != view.layout_id { panic!("CommandBufferRecordRenderPass::{}: Incompatible BufferView.", name); }
+        if DEBUG_MODE && view.count > 1 && !self.record.buffer.pool.device.features.contains(&Feature::MultiDrawIndirect) { panic!("CommandBufferRecordRenderPass::{}: More than one draw needs Feature::MultiDrawIndirect.", name); }
+    }
+
+    //one draw for every command in the view
+    #[inline]
+    pub fn draw_indirect(&mut self, buffer: &Buffer, view: &BufferView<DrawIndirectCommand>) -> &mut Self
+    {
+        self.check_indirect("draw_indirect", buffer, view);
+        unsafe { self.record.buffer.pool.device.logical_device.cmd_draw_indirect(self.record.buffer.command_buffer, buffer.buffer, view.offset_in_bytes as u64, view.count, view.stride); }
+        self
+    }
+
+    #[inline]
+    pub fn draw_indexed_indirect(&mut self, buffer: &Buffer, view: &BufferView<DrawIndexedIndirectCommand>) -> &mut Self
+    {
+        self.check_indirect("draw_indexed_indirect", buffer, view);
+        unsafe { self.record.buffer.pool.device.logical_device.cmd_draw_indexed_indirect(self.record.buffer.command_buffer, buffer.buffer, view.offset_in_bytes as u64, view.count, view.stride); }
+        self
+    }
+
+    //the number of draws is read from the GPU, at most the count of the view
+    #[inline]
+    pub fn draw_indirect_count(&mut self, buffer: &Buffer, view: &BufferView<DrawIndirectCommand>, count_buffer: &Buffer, count_view: &BufferView<IndirectCount>, count_index: u32) -> &mut Self
+    {
+        self.check_indirect_count("draw_indirect_count", buffer, view, count_buffer, count_view, count_index);
+        let count_offset_in_bytes = count_view.offset_in_bytes as u64 + count_index as u64 * count_view.stride as u64;
+        unsafe { self.record.buffer.pool.device.logical_device.cmd_draw_indirect_count(self.record.buffer.command_buffer, buffer.buffer, view.offset_in_bytes as u64, count_buffer.buffer, count_offset_in_bytes, view.count, view.stride); }
+        self
+    }
+
+    #[inline]
+    pub fn draw_indexed_indirect_count(&mut self, buffer: &Buffer, view: &BufferView<DrawIndexedIndirectCommand>, count_buffer: &Buffer, count_view: &BufferView<IndirectCount>, count_index: u32) -> &mut Self
+    {
+        self.check_indirect_count("draw_indexed_indirect_count", buffer, view, count_buffer, count_view, count_index);
+        let count_offset_in_bytes = count_view.offset_in_bytes as u64 + count_index as u64 * count_view.stride as u64;
+        unsafe { self.record.buffer.pool.device.logical_device.cmd_draw_indexed_indirect_count(self.record.buffer.command_buffer, buffer.buffer, view.offset_in_bytes as u64, count_buffer.buffer, count_offset_in_bytes, view.count, view.stride); }
+        self
+    }
+
+    #[inline(always)]
+    fn check_indirect_count<T>(&self, name: &str, buffer: &Buffer, view: &BufferView<T>, count_buffer: &Buffer, count_view: &BufferView<IndirectCount>, count_index: u32)
+    {
+        if DEBUG_MODE && buffer.layout_id != view.layout_id { panic!("CommandBufferRecordRenderPass::{}: Incompatible BufferView.", name); }
+        if DEBUG_MODE && count_buffer.layout_id != count_view.layout_id { panic!("CommandBufferRecordRenderPass::{}: Incompatible count BufferView.", name); }
+        if DEBUG_MODE && count_index >= count_view.count { panic!("CommandBufferRecordRenderPass::{}: Count index out of bounds.", name); }
+        if DEBUG_MODE && !self.record.buffer.pool.device.features.contains(&Feature::DrawIndirectCount) { panic!("CommandBufferRecordRenderPass::{}: This needs Feature::DrawIndirectCount.", name); }
+    }
 }
 
 impl Drop for CommandBufferRecordRenderPass<'_, '_>
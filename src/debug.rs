@@ -6,63 +6,199 @@ unsafe extern "system" fn vulkan_debug_utils_callback
     message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
     message_type: vk::DebugUtilsMessageTypeFlagsEXT,
     p_callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
-    _p_user_data: *mut std::ffi::c_void,
+    p_user_data: *mut std::ffi::c_void,
 ) -> vk::Bool32
 {
-    /*
-    let names: String = std::slice::from_raw_parts((*p_callback_data).p_objects, (*p_callback_data).object_count as usize)
-        .iter()
-        .flat_map(|obj|
-            if obj.p_object_name.is_null() { None }
-            else { Some(std::ffi::CStr::from_ptr(obj.p_object_name).to_str().unwrap()) }
-        )
-        .intersperse(", ")
-        .collect();
-    */
-    let ty = format!("{:?}", message_type).to_lowercase();
-    let message = unsafe { std::ffi::CStr::from_ptr((*p_callback_data).p_message) };
-
-    #[cfg(feature = "log")]
+    let state = unsafe { &*(p_user_data as *const DebugState) };
+    let message = unsafe { DebugMessage::from_vk(message_severity, message_type, &*p_callback_data) };
+    if message.id_name.contains("DEBUG-PRINTF")
     {
-        let level =
-            if message_severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR) { log::Level::Error }
-            else if message_severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::WARNING) { log::Level::Warn }
-            else if message_severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE) { log::Level::Debug }
-            else { log::Level::Trace };
-        log::log!(level, "[{ty}] {message:?}");
+        let printf = DebugPrintf::parse(&message.message);
+        match &state.printf_output
+        {
+            DebugPrintfOutput::Print => printf.print(),
+            DebugPrintfOutput::Callback(callback) => callback(&printf)
+        }
+        return vk::FALSE;
     }
-    
-    #[cfg(not(feature = "log"))]
+    //the messenger also receives info messages if debug printf is enabled
+    if message.severity < state.severity { return vk::FALSE; }
+    match &state.output
     {
-        let severity = format!("{:?}", message_severity).to_lowercase();
-        println!("[{}][{}] {:?}", severity, ty, message);
+        DebugOutput::Print => message.print(),
+        DebugOutput::Callback(callback) => callback(&message),
+        DebugOutput::Capture { panic_on_error } =>
+        {
+            //a panic cannot unwind through the driver, so it is deferred to raise_debug_error
+            if *panic_on_error && message.severity == DebugSeverity::Error
+            {
+                let mut error = state.error.lock().unwrap();
+                if error.is_none() { *error = Some(format!("[{}] {}", message.id_name, message.message)); }
+            }
+            state.captured.lock().unwrap().push(message);
+        }
     }
-
     vk::FALSE
 }
 
-pub(crate) fn create_instance(entry: &ash::Entry, instance_create_info: vk::InstanceCreateInfo) -> (ash::Instance, (ash::ext::debug_utils::Instance, vk::DebugUtilsMessengerEXT))
+impl DebugMessage
+{
+    unsafe fn from_vk(message_severity: vk::DebugUtilsMessageSeverityFlagsEXT, message_type: vk::DebugUtilsMessageTypeFlagsEXT, data: &vk::DebugUtilsMessengerCallbackDataEXT) -> Self
+    {
+        let string = |ptr: *const std::os::raw::c_char|
+            if ptr.is_null() { String::new() }
+            else { unsafe { std::ffi::CStr::from_ptr(ptr) }.to_string_lossy().into_owned() };
+        let severity =
+            if message_severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR) { DebugSeverity::Error }
+            else if message_severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::WARNING) { DebugSeverity::Warning }
+            else if message_severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::INFO) { DebugSeverity::Info }
+            else { DebugSeverity::Verbose };
+        let ty =
+            if message_type.contains(vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION) { DebugMessageType::Validation }
+            else if message_type.contains(vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE) { DebugMessageType::Performance }
+            else { DebugMessageType::General };
+        let objects =
+            if data.p_objects.is_null() { Vec::new() }
+            else
+            {
+                unsafe { std::slice::from_raw_parts(data.p_objects, data.object_count as usize) }
+                    .iter()
+                    .filter(|object| !object.p_object_name.is_null())
+                    .map(|object| string(object.p_object_name))
+                    .collect()
+            };
+        Self { severity, ty, id_name: string(data.p_message_id_name), message: string(data.p_message), objects }
+    }
+
+    fn print(&self)
+    {
+        let ty = format!("{:?}", self.ty).to_lowercase();
+        let message = &self.message;
+
+        #[cfg(feature = "log")]
+        {
+            let level = match self.severity
+            {
+                DebugSeverity::Error => log::Level::Error,
+                DebugSeverity::Warning => log::Level::Warn,
+                DebugSeverity::Info => log::Level::Debug,
+                DebugSeverity::Verbose => log::Level::Trace
+            };
+            log::log!(level, "[{ty}] {message:?}");
+        }
+
+        #[cfg(not(feature = "log"))]
+        {
+            let severity = format!("{:?}", self.severity).to_lowercase();
+            println!("[{}][{}] {:?}", severity, ty, message);
+        }
+    }
+}
+
+impl DebugPrintf
 {
+    //the layout of the message differs between validation layer versions, the printed text comes last
+    fn parse(message: &str) -> Self
+    {
+        let output = match (message.rfind(" | "), message.rfind('\n'))
+        {
+            (Some(index), _) => &message[index + 3..],
+            (None, Some(index)) => &message[index + 1..],
+            (None, None) => message
+        };
+        let (stage, invocation) = match message.find("Stage = ")
+        {
+            Some(index) =>
+            {
+                let rest = &message[index + 8..];
+                let end = rest.find('.').unwrap_or(rest.len());
+                let invocation = rest[end..].trim_start_matches('.');
+                let invocation = invocation[..invocation.find(['|', '\n']).unwrap_or(invocation.len())].trim();
+                (Some(rest[..end].trim().to_owned()), if invocation.is_empty() { None } else { Some(invocation.to_owned()) })
+            },
+            None => (None, None)
+        };
+        Self { stage, invocation, message: output.trim().to_owned() }
+    }
+
+    fn print(&self)
+    {
+        let stage = self.stage.as_deref().unwrap_or("shader");
+        let message = &self.message;
+
+        #[cfg(feature = "log")]
+        match &self.invocation
+        {
+            Some(invocation) => log::info!(target: "gru_vulkan::printf", "[{stage}][{invocation}] {message}"),
+            None => log::info!(target: "gru_vulkan::printf", "[{stage}] {message}")
+        }
+
+        #[cfg(not(feature = "log"))]
+        match &self.invocation
+        {
+            Some(invocation) => println!("[printf][{}][{}] {}", stage, invocation, message),
+            None => println!("[printf][{}] {}", stage, message)
+        }
+    }
+}
+
+impl RawInstance
+{
+    //panics with the first error captured by DebugOutput::Capture { panic_on_error: true }
+    pub(crate) fn raise_debug_error(&self)
+    {
+        if let Some(error) = self.debug_state.error.lock().unwrap().take() { panic!("Vulkan error: {}", error); }
+    }
+}
+
+impl ValidationFeatures
+{
+    pub(crate) fn vk_features(&self) -> Vec<vk::ValidationFeatureEnableEXT>
+    {
+        let mut features = Vec::new();
+        if self.synchronization { features.push(vk::ValidationFeatureEnableEXT::SYNCHRONIZATION_VALIDATION); }
+        if self.best_practices { features.push(vk::ValidationFeatureEnableEXT::BEST_PRACTICES); }
+        if self.gpu_assisted { features.push(vk::ValidationFeatureEnableEXT::GPU_ASSISTED); }
+        if self.debug_printf { features.push(vk::ValidationFeatureEnableEXT::DEBUG_PRINTF); }
+        features
+    }
+}
+
+pub(crate) fn create_instance(entry: &ash::Entry, instance_create_info: vk::InstanceCreateInfo, message_types: &[DebugMessageType], debug_printf: bool, state: &DebugState) -> Result<(ash::Instance, (ash::ext::debug_utils::Instance, vk::DebugUtilsMessengerEXT)), Error>
+{
+    let message_severity =
+    [
+        (DebugSeverity::Verbose, vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE),
+        (DebugSeverity::Info, vk::DebugUtilsMessageSeverityFlagsEXT::INFO),
+        (DebugSeverity::Warning, vk::DebugUtilsMessageSeverityFlagsEXT::WARNING),
+        (DebugSeverity::Error, vk::DebugUtilsMessageSeverityFlagsEXT::ERROR)
+    ].iter().filter(|(level, _)| *level >= state.severity || (debug_printf && *level == DebugSeverity::Info)).fold(vk::DebugUtilsMessageSeverityFlagsEXT::empty(), |flags, (_, flag)| flags | *flag);
+    let message_type = message_types.iter().fold(vk::DebugUtilsMessageTypeFlagsEXT::empty(), |flags, ty| flags | match ty
+    {
+        DebugMessageType::General => vk::DebugUtilsMessageTypeFlagsEXT::GENERAL,
+        DebugMessageType::Validation => vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION,
+        DebugMessageType::Performance => vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
+    });
     let mut debug_create_info = vk::DebugUtilsMessengerCreateInfoEXT::default()
-        .message_severity
-        (
-            vk::DebugUtilsMessageSeverityFlagsEXT::ERROR
-          | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING
-          | vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE
-          | vk::DebugUtilsMessageSeverityFlagsEXT::INFO
-        ).message_type
-        (
-            vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
-          | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
-          | vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
-        ).pfn_user_callback(Some(vulkan_debug_utils_callback));
+        .message_severity(message_severity)
+        .message_type(message_type)
+        .pfn_user_callback(Some(vulkan_debug_utils_callback))
+        .user_data(state as *const DebugState as *mut std::ffi::c_void);
     let instance_create_info = instance_create_info.push_next(&mut debug_create_info);
-    let instance = unsafe { entry.create_instance(&instance_create_info, None).unwrap() };
+    let instance = unsafe { entry.create_instance(&instance_create_info, None) }?;
 
     let debug_utils = ash::ext::debug_utils::Instance::new(&entry, &instance);
-    let debug_utils_messenger = unsafe { debug_utils.create_debug_utils_messenger(&debug_create_info, None).unwrap() };
+    let debug_utils_messenger = match unsafe { debug_utils.create_debug_utils_messenger(&debug_create_info, None) }
+    {
+        Ok(debug_utils_messenger) => debug_utils_messenger,
+        Err(error) =>
+        {
+            unsafe { instance.destroy_instance(None); }
+            return Err(error.into());
+        }
+    };
     let debug_utils = (debug_utils, debug_utils_messenger);
-    (instance, debug_utils)
+    Ok((instance, debug_utils))
 }
 
 impl<'a> CommandBufferRecord<'a>
@@ -70,7 +206,6 @@ impl<'a> CommandBufferRecord<'a>
     #[inline]
     pub fn debug_insert_label(&mut self, label: &str) -> &mut Self
     {
-        #[cfg(debug_assertions)]
         if let Some(debug_utils) = &self.buffer.pool.device.debug_utils
         {
             let cstr = std::ffi::CString::new(label).unwrap();
@@ -100,7 +235,6 @@ trait NameableObject: Sized
     fn device(&self) -> &RawDevice;
     fn debug_named(self, name: &str) -> Self
     {
-        #[cfg(debug_assertions)]
         if let Some(debug_utils) = &self.device().debug_utils
         {
             let cstr = std::ffi::CString::new(name).unwrap();
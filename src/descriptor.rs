@@ -2,8 +2,9 @@ use super::*;
 
 impl Device
 {
-	pub fn new_descriptor_set_layout(&self, set: u32, bindings: Vec<DescriptorBindingInfo>) -> DescriptorSetLayout
+	pub fn new_descriptor_set_layout(&self, set: u32, bindings: Vec<DescriptorBindingInfo>) -> Result<DescriptorSetLayout, Error>
     {
+        if bindings.iter().filter(|binding| binding.is_dynamic()).count() > MAX_DYNAMIC_BINDINGS { return Err(Error::Misuse(format!("Device::new_descriptor_set_layout: More than {} dynamic bindings.", MAX_DYNAMIC_BINDINGS))); }
         let descriptor_set_layout_bindings: Vec<_> = bindings.iter().enumerate().map(|(id, binding)|
         {
             let stage_flags =
@@ -17,21 +18,23 @@ impl Device
                 .stage_flags(stage_flags)
         }).collect();
         let descriptor_set_layout_info = vk::DescriptorSetLayoutCreateInfo::default().bindings(&descriptor_set_layout_bindings);
-        let descriptor_set_layout = unsafe { self.0.logical_device.create_descriptor_set_layout(&descriptor_set_layout_info, None) }.unwrap();
-        DescriptorSetLayout(Arc::new(RawDescriptorSetLayout { device: self.0.clone(), set, bindings: Box::from(bindings), descriptor_set_layout }))
+        let descriptor_set_layout = unsafe { self.0.logical_device.create_descriptor_set_layout(&descriptor_set_layout_info, None) }?;
+        Ok(DescriptorSetLayout(Arc::new(RawDescriptorSetLayout { device: self.0.clone(), set, bindings: Box::from(bindings), descriptor_set_layout })))
     }
 
-    pub fn new_descriptor_sets(&self, set_layouts: &[(&DescriptorSetLayout, u32)]) -> Vec<Vec<DescriptorSet>>
+    pub fn new_descriptor_sets(&self, set_layouts: &[(&DescriptorSetLayout, u32)]) -> Result<Vec<Vec<DescriptorSet>>, Error>
     {
-        let (mut set_count, mut storage_count, mut struct_count, mut sampler_count, mut input_attachment_count) = (0, 0, 0, 0, 0);
+        let (mut set_count, mut storage_count, mut struct_count, mut sampler_count, mut input_attachment_count, mut dynamic_storage_count, mut dynamic_struct_count) = (0, 0, 0, 0, 0, 0, 0);
         for (layout, count) in set_layouts
         {
             set_count += count;
-            let (a, b, c, d) = layout.0.type_count();
+            let (a, b, c, d, e, f) = layout.0.type_count();
             storage_count += count * a;
             struct_count += count * b;
             sampler_count += count * c;
             input_attachment_count += count * d;
+            dynamic_storage_count += count * e;
+            dynamic_struct_count += count * f;
         }
         
         let mut pool_sizes = vec![];
@@ -67,11 +70,27 @@ impl Device
                 descriptor_count: input_attachment_count,
             })
         };
+        if dynamic_storage_count > 0
+        {
+            pool_sizes.push(vk::DescriptorPoolSize
+            {
+                ty: vk::DescriptorType::STORAGE_BUFFER_DYNAMIC,
+                descriptor_count: dynamic_storage_count,
+            })
+        };
+        if dynamic_struct_count > 0
+        {
+            pool_sizes.push(vk::DescriptorPoolSize
+            {
+                ty: vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC,
+                descriptor_count: dynamic_struct_count,
+            })
+        };
 
         let descriptor_pool_info = vk::DescriptorPoolCreateInfo::default()
             .max_sets(set_count)
             .pool_sizes(&pool_sizes);
-        let descriptor_pool = unsafe { self.0.logical_device.create_descriptor_pool(&descriptor_pool_info, None) }.unwrap();
+        let descriptor_pool = unsafe { self.0.logical_device.create_descriptor_pool(&descriptor_pool_info, None) }?;
         let pool_arc = Arc::new(DescriptorPool
         {
             device: self.0.clone(),
@@ -85,8 +104,8 @@ impl Device
             let descriptor_set_allocate_info = vk::DescriptorSetAllocateInfo::default()
                 .descriptor_pool(descriptor_pool)
                 .set_layouts(&layouts[..]);
-            let descriptor_sets = unsafe { self.0.logical_device.allocate_descriptor_sets(&descriptor_set_allocate_info) }.unwrap();
-            descriptor_sets.iter().map(|set| DescriptorSet { pool: pool_arc.clone(), descriptor_set: *set, layout: layout.0.clone() }).collect()
+            let descriptor_sets = unsafe { self.0.logical_device.allocate_descriptor_sets(&descriptor_set_allocate_info) }?;
+            Ok(descriptor_sets.iter().map(|set| DescriptorSet { pool: pool_arc.clone(), descriptor_set: *set, layout: layout.0.clone(), buffers: Vec::new(), dynamic_views: Vec::new() }).collect())
         }).collect()
     }
 }
@@ -136,6 +155,26 @@ impl DescriptorBindingInfo
         }
     }
 
+    pub fn from_dynamic_storage<T: StorageStructReprC>(visibility: DescriptorVisibility) -> Self
+    {
+        Self
+        {
+            ty: DescriptorBindingType::DynamicStorage,
+            count: 1,
+            visibility
+        }
+    }
+
+    pub fn from_dynamic_struct<T: DescriptorStructReprC>(visibility: DescriptorVisibility) -> Self
+    {
+        Self
+        {
+            ty: DescriptorBindingType::DynamicStruct { size_in_bytes: std::mem::size_of::<T>() as u32 },
+            count: 1,
+            visibility
+        }
+    }
+
     pub fn from_sampler(image_channel_type: ImageChannelType, count: u32, visibility: DescriptorVisibility) -> Self
     {
     	Self
@@ -168,57 +207,145 @@ impl DescriptorBindingInfo
             DescriptorBindingType::Storage => vk::DescriptorType::STORAGE_BUFFER,
             DescriptorBindingType::Struct { .. } => vk::DescriptorType::UNIFORM_BUFFER,
             DescriptorBindingType::Sampler { .. } => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
-            DescriptorBindingType::SubpassInput { .. } => vk::DescriptorType::INPUT_ATTACHMENT
+            DescriptorBindingType::SubpassInput { .. } => vk::DescriptorType::INPUT_ATTACHMENT,
+            DescriptorBindingType::DynamicStorage => vk::DescriptorType::STORAGE_BUFFER_DYNAMIC,
+            DescriptorBindingType::DynamicStruct { .. } => vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC
         }
     }
 
-    fn type_count(&self) -> (u32, u32, u32, u32)
+    fn type_count(&self) -> (u32, u32, u32, u32, u32, u32)
     {
         match self.ty
         {
-            DescriptorBindingType::Storage => (1, 0, 0, 0),
-            DescriptorBindingType::Struct { .. } => (0, self.count, 0, 0),
-            DescriptorBindingType::Sampler { .. } => (0, 0, self.count, 0),
-            DescriptorBindingType::SubpassInput { .. } => (0, 0, 0, 1)
+            DescriptorBindingType::Storage => (1, 0, 0, 0, 0, 0),
+            DescriptorBindingType::Struct { .. } => (0, self.count, 0, 0, 0, 0),
+            DescriptorBindingType::Sampler { .. } => (0, 0, self.count, 0, 0, 0),
+            DescriptorBindingType::SubpassInput { .. } => (0, 0, 0, 1, 0, 0),
+            DescriptorBindingType::DynamicStorage => (0, 0, 0, 0, 1, 0),
+            DescriptorBindingType::DynamicStruct { .. } => (0, 0, 0, 0, 0, 1)
         }
     }
+
+    fn is_dynamic(&self) -> bool
+    {
+        matches!(self.ty, DescriptorBindingType::DynamicStorage | DescriptorBindingType::DynamicStruct { .. })
+    }
 }
 
 impl RawDescriptorSetLayout
 {
-    fn type_count(&self) -> (u32, u32, u32, u32)
+    fn type_count(&self) -> (u32, u32, u32, u32, u32, u32)
     {
-        let (mut storage_count, mut struct_count, mut sampler_count, mut input_attachment_count) = (0, 0, 0, 0);
+        let (mut storage_count, mut struct_count, mut sampler_count, mut input_attachment_count, mut dynamic_storage_count, mut dynamic_struct_count) = (0, 0, 0, 0, 0, 0);
         for binding in self.bindings.iter()
         {
-            let (a, b, c, d) = binding.type_count();
+            let (a, b, c, d, e, f) = binding.type_count();
             storage_count += a;
             struct_count += b;
             sampler_count += c;
             input_attachment_count += d;
+            dynamic_storage_count += e;
+            dynamic_struct_count += f;
+        }
+        (storage_count, struct_count, sampler_count, input_attachment_count, dynamic_storage_count, dynamic_struct_count)
+    }
+
+    //dynamic offsets are consumed in binding order
+    pub(crate) fn dynamic_count(&self) -> usize
+    {
+        self.bindings.iter().filter(|binding| binding.is_dynamic()).count()
+    }
+}
+
+impl DynamicOffset
+{
+    //the element at index of a view used with a dynamic binding
+    #[inline]
+    pub fn new<T>(view: &BufferView<T>, index: u32) -> Self
+    {
+        if DEBUG_MODE && index >= view.count { panic!("DynamicOffset::new: Index out of bounds."); }
+        Self
+        {
+            layout_id: view.layout_id,
+            offset_in_bytes: index * view.stride
         }
-        (storage_count, struct_count, sampler_count, input_attachment_count)
+    }
+}
+
+//None if a buffer tracked by a descriptor set is still the current one
+pub(crate) fn buffer_error(current_generation: &std::sync::Weak<std::sync::atomic::AtomicU32>, generation: u32) -> Option<&'static str>
+{
+    match current_generation.upgrade()
+    {
+        None => Some("has been dropped, update the descriptor set"),
+        Some(current_generation) if current_generation.load(std::sync::atomic::Ordering::Relaxed) != generation => Some("is stale, update the descriptor set with the grown GpuVec"),
+        Some(_) => None
     }
 }
 
 impl DescriptorSet
 {
+    fn track_buffer(&mut self, binding: u32, buffer: &Buffer)
+    {
+        if !DEBUG_MODE { return; }
+        self.buffers.retain(|(tracked, _, _)| *tracked != binding);
+        self.buffers.push((binding, Arc::downgrade(&buffer.current_generation), buffer.generation));
+    }
+
+    fn track_dynamic_view(&mut self, binding: u32, layout_id: u32)
+    {
+        self.dynamic_views.retain(|(tracked, _)| *tracked != binding);
+        self.dynamic_views.push((binding, layout_id));
+        self.dynamic_views.sort_by_key(|(binding, _)| *binding);
+    }
+
+    //the offsets must belong to the views the dynamic bindings were updated with, in binding order
+    pub(crate) fn check_dynamic_offsets(&self, offsets: &[DynamicOffset])
+    {
+        if offsets.len() != self.layout.dynamic_count() { panic!("DescriptorSet: Wrong amount of dynamic offsets: {} vs {}.", offsets.len(), self.layout.dynamic_count()); }
+        if self.dynamic_views.len() != offsets.len() { panic!("DescriptorSet: Not every dynamic binding has been updated."); }
+        for ((binding, layout_id), offset) in self.dynamic_views.iter().zip(offsets)
+        {
+            if *layout_id != offset.layout_id { panic!("DescriptorSet: The dynamic offset for binding {} belongs to an incompatible BufferView.", binding); }
+            let align = match self.layout.bindings[*binding as usize].ty
+            {
+                DescriptorBindingType::DynamicStorage => self.pool.device.props.min_storage_buffer_offset_alignment,
+                _ => self.pool.device.props.min_uniform_buffer_offset_alignment
+            };
+            if !(offset.offset_in_bytes as u64).is_multiple_of(align) { panic!("DescriptorSet: The dynamic offset for binding {} is not aligned to {} bytes.", binding, align); }
+        }
+    }
+
+    //e.g. the old buffer of a GpuVec after it has grown
+    pub(crate) fn check_buffers(&self)
+    {
+        for (binding, current_generation, generation) in &self.buffers
+        {
+            if let Some(error) = buffer_error(current_generation, *generation) { panic!("DescriptorSet: The buffer at binding {} {}.", binding, error); }
+        }
+    }
+
     pub fn update_storage<T: StorageStructReprC>(&mut self, binding: u32, buffer: &Buffer, view: &BufferView<T>)
     {
         if DEBUG_MODE && view.layout_id != buffer.layout_id { panic!("DescriptorSet::update_storage: Incompatible BufferView"); }
+        self.track_buffer(binding, buffer);
         let layout = &self.layout.bindings[binding as usize];
-        match layout.ty
+        let dynamic = match layout.ty
         {
-            DescriptorBindingType::Storage => {},
-        	DescriptorBindingType::Struct { .. } => panic!("DescriptorSet::update_storage: Incompatible DescriptorBindingType."),
+            DescriptorBindingType::Storage => false,
+            DescriptorBindingType::DynamicStorage => true,
+        	DescriptorBindingType::Struct { .. } | DescriptorBindingType::DynamicStruct { .. } => panic!("DescriptorSet::update_storage: Incompatible DescriptorBindingType."),
             DescriptorBindingType::Sampler { .. } => panic!("DescriptorSet::update_storage: Incompatible DescriptorBindingType."),
             DescriptorBindingType::SubpassInput { .. } => panic!("DescriptorSet::update_storage: Incompatible DescriptorBindingType.")
         };
+        let align = self.pool.device.props.min_storage_buffer_offset_alignment;
+        if dynamic && !(view.stride as u64).is_multiple_of(align) { panic!("DescriptorSet::update_storage: The stride of the view is not aligned to {} bytes, add it with BufferTypeBuilder::add_dynamic_storage.", align); }
+        //a dynamic binding sees one element at the offset chosen at bind time
         let buffer_info = vk::DescriptorBufferInfo 
         {
             buffer: buffer.buffer,
             offset: view.offset_in_bytes as u64,
-            range: (view.stride * view.count()) as u64,
+            range: if dynamic { view.stride as u64 } else { (view.stride * view.count()) as u64 },
         };
         let descriptor_sets_write =
         [
@@ -226,24 +353,31 @@ impl DescriptorSet
                 .dst_set(self.descriptor_set)
                 .dst_binding(binding)
                 .dst_array_element(0)
-                .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+                .descriptor_type(layout.vk_type())
                 .buffer_info(std::slice::from_ref(&buffer_info))
         ];
+        if dynamic { self.track_dynamic_view(binding, view.layout_id); }
         unsafe { self.pool.device.logical_device.update_descriptor_sets(&descriptor_sets_write, &[]) };
     }
 
     pub fn update_struct<T: DescriptorStructReprC>(&mut self, binding: u32, buffer: &Buffer, view: &BufferView<T>)
     {
         if DEBUG_MODE && view.layout_id != buffer.layout_id { panic!("DescriptorSet::update_struct: Incompatible BufferView"); }
+        self.track_buffer(binding, buffer);
         let layout = &self.layout.bindings[binding as usize];
-        if view.count != layout.count { panic!("DescriptorSet::update_struct: Wrong amount of uniforms: {} vs {}.", view.count, layout.count); }
-        match layout.ty
+        let dynamic = match layout.ty
         {
-            DescriptorBindingType::Storage => panic!("DescriptorSet::update_struct: Incompatible DescriptorBindingType."),
-        	DescriptorBindingType::Struct { size_in_bytes } => if std::mem::size_of::<T>() != size_in_bytes as usize { panic!("DescriptorSet::update_struct: Incompatible struct size."); },
+            DescriptorBindingType::Storage | DescriptorBindingType::DynamicStorage => panic!("DescriptorSet::update_struct: Incompatible DescriptorBindingType."),
+        	DescriptorBindingType::Struct { size_in_bytes } | DescriptorBindingType::DynamicStruct { size_in_bytes } =>
+            {
+                if std::mem::size_of::<T>() != size_in_bytes as usize { panic!("DescriptorSet::update_struct: Incompatible struct size."); }
+                layout.is_dynamic()
+            },
             DescriptorBindingType::Sampler { .. } => panic!("DescriptorSet::update_struct: Incompatible DescriptorBindingType."),
             DescriptorBindingType::SubpassInput { .. } => panic!("DescriptorSet::update_struct: Incompatible DescriptorBindingType.")
         };
+        //a dynamic binding takes any amount of uniforms, one of them is chosen at bind time
+        if !dynamic && view.count != layout.count { panic!("DescriptorSet::update_struct: Wrong amount of uniforms: {} vs {}.", view.count, layout.count); }
         let buffer_infos: Vec<_> = (0..layout.count).map(|i| vk::DescriptorBufferInfo 
         {
             buffer: buffer.buffer,
@@ -256,9 +390,10 @@ impl DescriptorSet
                 .dst_set(self.descriptor_set)
                 .dst_binding(binding)
                 .dst_array_element(0)
-                .descriptor_type(vk::DescriptorType::UNIFORM_BUFFER)
+                .descriptor_type(layout.vk_type())
                 .buffer_info(&buffer_infos)
         ];
+        if dynamic { self.track_dynamic_view(binding, view.layout_id); }
         unsafe { self.pool.device.logical_device.update_descriptor_sets(&descriptor_sets_write, &[]) };
     }
 
@@ -268,8 +403,8 @@ impl DescriptorSet
         if images.len() as u32 != layout.count { panic!("DescriptorSet::update_sampler: Wrong amount of images: {} vs {}.", images.len(), layout.count); }
         match layout.ty
         {
-            DescriptorBindingType::Storage => panic!("DescriptorSet::update_sampler: Incompatible DescriptorBindingType."),
-        	DescriptorBindingType::Struct { .. } => panic!("DescriptorSet::update_sampler: Incompatible DescriptorBindingType."),
+            DescriptorBindingType::Storage | DescriptorBindingType::DynamicStorage => panic!("DescriptorSet::update_sampler: Incompatible DescriptorBindingType."),
+        	DescriptorBindingType::Struct { .. } | DescriptorBindingType::DynamicStruct { .. } => panic!("DescriptorSet::update_sampler: Incompatible DescriptorBindingType."),
             DescriptorBindingType::Sampler { image_channel_type } => for (i, image) in images.iter().enumerate()
             {
                 if image.image_type.channel != image_channel_type { panic!("DescriptorSet::update_sampler: Incompatible ImageType for image {}.", i); }
@@ -301,8 +436,8 @@ impl DescriptorSet
         let layout = &self.layout.bindings[binding as usize];
         match layout.ty
         {
-            DescriptorBindingType::Storage => panic!("DescriptorSet::update_input_attachment: Incompatible DescriptorBindingType."),
-            DescriptorBindingType::Struct { .. } => panic!("DescriptorSet::update_input_attachment: Incompatible DescriptorBindingType."),
+            DescriptorBindingType::Storage | DescriptorBindingType::DynamicStorage => panic!("DescriptorSet::update_input_attachment: Incompatible DescriptorBindingType."),
+            DescriptorBindingType::Struct { .. } | DescriptorBindingType::DynamicStruct { .. } => panic!("DescriptorSet::update_input_attachment: Incompatible DescriptorBindingType."),
             DescriptorBindingType::Sampler { .. } => panic!("DescriptorSet::update_input_attachment: Incompatible DescriptorBindingType."),
             DescriptorBindingType::SubpassInput { image_channel_type } =>
                 if image.image_type.channel != image_channel_type { panic!("DescriptorSet::update_input_attachment: Incompatible ImageType."); }
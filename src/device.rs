@@ -12,7 +12,60 @@ impl QueueFamily
     pub fn supports_compute(&self) -> bool { self.flags.contains(vk::QueueFlags::COMPUTE) }
     pub fn supports_transfer(&self) -> bool { self.flags.contains(vk::QueueFlags::TRANSFER) }
     //pub fn supports_sparse_binding(&self) -> bool { self.queue_family_properties.queue_flags.contains(vk::QueueFlags::SPARSE_BINDING) }
-    pub fn supports_surface(&self) -> bool { self.surface_support }
+    pub fn supports_surface(&self, surface: &Surface) -> Result<bool, Error> { surface.supports(self.physical_device, self.index) }
+}
+
+impl RawDevice
+{
+    //the layout swapchain images are left in by render passes, offscreen swapchains on devices without swapchain support use GENERAL
+    pub(crate) fn present_layout(&self) -> vk::ImageLayout
+    {
+        if self.swapchain_support { vk::ImageLayout::PRESENT_SRC_KHR } else { vk::ImageLayout::GENERAL }
+    }
+
+    //allocates and binds memory, destroys the buffer on failure
+    pub(crate) fn allocate_buffer_memory(&self, buffer: vk::Buffer, allocation_create_desc: &alloc::AllocationCreateDesc) -> Result<alloc::Allocation, Error>
+    {
+        let mut allocator = self.allocator.as_ref().unwrap().lock().unwrap();
+        let allocation = match allocator.allocate(allocation_create_desc)
+        {
+            Ok(allocation) => allocation,
+            Err(error) =>
+            {
+                unsafe { self.logical_device.destroy_buffer(buffer, None); }
+                return Err(error.into());
+            }
+        };
+        if let Err(error) = unsafe { self.logical_device.bind_buffer_memory(buffer, allocation.memory(), allocation.offset()) }
+        {
+            unsafe { self.logical_device.destroy_buffer(buffer, None); }
+            allocator.free(allocation)?;
+            return Err(error.into());
+        }
+        Ok(allocation)
+    }
+
+    //allocates and binds memory, destroys the image on failure
+    pub(crate) fn allocate_image_memory(&self, image: vk::Image, allocation_create_desc: &alloc::AllocationCreateDesc) -> Result<alloc::Allocation, Error>
+    {
+        let mut allocator = self.allocator.as_ref().unwrap().lock().unwrap();
+        let allocation = match allocator.allocate(allocation_create_desc)
+        {
+            Ok(allocation) => allocation,
+            Err(error) =>
+            {
+                unsafe { self.logical_device.destroy_image(image, None); }
+                return Err(error.into());
+            }
+        };
+        if let Err(error) = unsafe { self.logical_device.bind_image_memory(image, allocation.memory(), allocation.offset()) }
+        {
+            unsafe { self.logical_device.destroy_image(image, None); }
+            allocator.free(allocation)?;
+            return Err(error.into());
+        }
+        Ok(allocation)
+    }
 }
 
 impl Device
@@ -22,15 +75,37 @@ impl Device
         unsafe { self.0.logical_device.device_wait_idle() }.unwrap();
     }
 
+    pub fn enabled_features(&self) -> &[Feature]
+    {
+        &self.0.features
+    }
+
+    pub fn feature_enabled(&self, feature: Feature) -> bool
+    {
+        self.0.features.contains(&feature)
+    }
+
+    //drains the messages collected with DebugOutput::Capture
+    pub fn take_debug_messages(&self) -> Vec<DebugMessage>
+    {
+        self.0.instance.take_debug_messages()
+    }
+
+    //the instance must have been built with a window of the same display
+    pub fn new_surface(&self, window: &dyn HasBothHandles) -> Result<Surface, Error>
+    {
+        Surface::new(&self.0.instance.0, window)
+    }
+
     pub fn get_queue_family(&self, queue_family_info: &QueueFamilyInfo) -> &QueueFamily
     {
         &self.0.queue_families.iter().filter(|family| family.index == queue_family_info.index).nth(0).unwrap()
     }
 
-    pub fn new_compute(&self, compute_shader_spirv: Shader, layout: &PipelineLayout) -> Compute
+    pub fn new_compute(&self, compute_shader_spirv: Shader, layout: &PipelineLayout) -> Result<Compute, Error>
     {
         let compute_shader_create_info = vk::ShaderModuleCreateInfo::default().code(&compute_shader_spirv);
-        let compute_shader_module = unsafe { self.0.logical_device.create_shader_module(&compute_shader_create_info, None) }.unwrap();
+        let compute_shader_module = unsafe { self.0.logical_device.create_shader_module(&compute_shader_create_info, None) }?;
         let main_function_name = std::ffi::CString::new("main").unwrap();
         let compute_shader_stage = vk::PipelineShaderStageCreateInfo::default()
             .stage(vk::ShaderStageFlags::COMPUTE)
@@ -39,24 +114,25 @@ impl Device
         let compute_info = vk::ComputePipelineCreateInfo::default()
             .stage(compute_shader_stage)
             .layout(layout.layout);
-        let compute = unsafe { self.0.logical_device.create_compute_pipelines(vk::PipelineCache::null(), &[compute_info], None) }.unwrap()[0];
+        let compute = unsafe { self.0.logical_device.create_compute_pipelines(vk::PipelineCache::null(), &[compute_info], None) };
         unsafe { self.0.logical_device.destroy_shader_module(compute_shader_module, None); }
-        Compute { device: self.0.clone(), compute }
+        let compute = compute.map_err(|(_, result)| result)?[0];
+        Ok(Compute { device: self.0.clone(), compute })
     }
 
-    pub fn new_semaphore(&self, wait_stage: WaitStage) -> Semaphore
+    pub fn new_semaphore(&self, wait_stage: WaitStage) -> Result<Semaphore, Error>
     {
         let semaphore_create_info = vk::SemaphoreCreateInfo::default();
-        let semaphore = unsafe { self.0.logical_device.create_semaphore(&semaphore_create_info, None) }.unwrap();
-        Semaphore { device: self.0.clone(), semaphore, wait_stage: wait_stage.vk_mask() }
+        let semaphore = unsafe { self.0.logical_device.create_semaphore(&semaphore_create_info, None) }?;
+        Ok(Semaphore { device: self.0.clone(), semaphore, wait_stage: wait_stage.vk_mask() })
     }
 
-    pub fn new_fence(&self, signaled: bool) -> Fence
+    pub fn new_fence(&self, signaled: bool) -> Result<Fence, Error>
     {
         let mut fence_create_info = vk::FenceCreateInfo::default();
         if signaled { fence_create_info = fence_create_info.flags(vk::FenceCreateFlags::SIGNALED) };
-        let fence = unsafe { self.0.logical_device.create_fence(&fence_create_info, None) }.unwrap();
-        Fence { device: self.0.clone(), fence }
+        let fence = unsafe { self.0.logical_device.create_fence(&fence_create_info, None) }?;
+        Ok(Fence { device: self.0.clone(), fence })
     }
 }
 
@@ -1,18 +1,25 @@
 use super::*;
 
-impl Drop for Instance
+impl Drop for RawInstance
 {
     fn drop(&mut self)
     {
         unsafe
         {
-            if let Some(surface) = &self.surface { surface.loader.destroy_surface(surface.surface, None); }
             if let Some((debug_utils, debug_utils_messenger)) = &self.debug { debug_utils.destroy_debug_utils_messenger(*debug_utils_messenger, None); }
             self.instance.destroy_instance(None);
         }
     }
 }
 
+impl Drop for RawSurface
+{
+    fn drop(&mut self)
+    {
+        unsafe { self.loader.destroy_surface(self.surface, None); }
+    }
+}
+
 impl Drop for RawDevice
 {
     fn drop(&mut self)
@@ -37,6 +44,15 @@ impl Drop for Swapchain
     }
 }
 
+impl Drop for OffscreenSwapchain
+{
+    fn drop(&mut self)
+    {
+        //the pending readback still uses the images and the command buffer
+        if self.pending.get().is_some() { self.fence.wait(); }
+    }
+}
+
 impl Drop for Buffer
 {
     fn drop(&mut self)
@@ -46,6 +62,15 @@ impl Drop for Buffer
     }
 }
 
+impl Drop for StagingRing
+{
+    fn drop(&mut self)
+    {
+        unsafe { self.device.logical_device.destroy_buffer(self.buffer, None); }
+        self.device.allocator.as_ref().unwrap().lock().unwrap().free(self.allocation.take().unwrap()).unwrap();
+    }
+}
+
 impl Drop for Image
 {
     fn drop(&mut self)
@@ -0,0 +1,74 @@
+use super::*;
+
+impl From<vk::Result> for Error
+{
+    fn from(result: vk::Result) -> Self
+    {
+        match result
+        {
+            vk::Result::ERROR_OUT_OF_HOST_MEMORY => Self::OutOfHostMemory,
+            vk::Result::ERROR_OUT_OF_DEVICE_MEMORY | vk::Result::ERROR_OUT_OF_POOL_MEMORY | vk::Result::ERROR_FRAGMENTED_POOL => Self::OutOfDeviceMemory,
+            vk::Result::ERROR_DEVICE_LOST => Self::DeviceLost,
+            vk::Result::ERROR_SURFACE_LOST_KHR => Self::SurfaceLost,
+            vk::Result::ERROR_FORMAT_NOT_SUPPORTED => Self::UnsupportedFormat,
+            vk::Result::ERROR_FEATURE_NOT_PRESENT | vk::Result::ERROR_EXTENSION_NOT_PRESENT | vk::Result::ERROR_LAYER_NOT_PRESENT => Self::UnsupportedFeature,
+            result => Self::Vulkan(result)
+        }
+    }
+}
+
+impl From<gpu_allocator::AllocationError> for Error
+{
+    fn from(error: gpu_allocator::AllocationError) -> Self
+    {
+        match error
+        {
+            gpu_allocator::AllocationError::OutOfMemory => Self::OutOfDeviceMemory,
+            error => Self::Allocation(error.to_string())
+        }
+    }
+}
+
+impl From<ash::LoadingError> for Error
+{
+    fn from(error: ash::LoadingError) -> Self
+    {
+        Self::Loading(error.to_string())
+    }
+}
+
+impl From<raw_window_handle::HandleError> for Error
+{
+    fn from(error: raw_window_handle::HandleError) -> Self
+    {
+        match error
+        {
+            raw_window_handle::HandleError::NotSupported => Self::UnsupportedFeature,
+            _ => Self::SurfaceLost
+        }
+    }
+}
+
+impl std::fmt::Display for Error
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            Self::OutOfHostMemory => write!(f, "out of host memory"),
+            Self::OutOfDeviceMemory => write!(f, "out of device memory"),
+            Self::DeviceLost => write!(f, "device lost"),
+            Self::SurfaceLost => write!(f, "surface lost"),
+            Self::UnsupportedFormat => write!(f, "unsupported format"),
+            Self::UnsupportedFeature => write!(f, "unsupported feature"),
+            Self::MissingFeatures(features) => write!(f, "missing required features: {features:?}"),
+            Self::NoSuitableDevice => write!(f, "no suitable physical device"),
+            Self::Misuse(message) => write!(f, "misuse: {message}"),
+            Self::Loading(message) => write!(f, "loading Vulkan failed: {message}"),
+            Self::Allocation(message) => write!(f, "allocation failed: {message}"),
+            Self::Vulkan(result) => write!(f, "Vulkan error: {result}")
+        }
+    }
+}
+
+impl std::error::Error for Error { }
@@ -0,0 +1,190 @@
+use super::*;
+
+macro_rules! features
+{
+    ($($feature: ident => $api: ident, $structure: ident.$field: ident;)+) =>
+    {
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum Feature
+        {
+            $($feature),+
+        }
+
+        impl Feature
+        {
+            pub const ALL: &'static [Feature] = &[$(Feature::$feature),+];
+
+            //the Vulkan version this feature became core in
+            pub const fn api_version(&self) -> ApiVersion
+            {
+                match self
+                {
+                    $(Feature::$feature => ApiVersion::$api),+
+                }
+            }
+
+            fn get(&self, structs: &FeatureStructs) -> bool
+            {
+                match self
+                {
+                    $(Feature::$feature => structs.$structure.$field == vk::TRUE),+
+                }
+            }
+
+            fn set(&self, structs: &mut FeatureStructs)
+            {
+                match self
+                {
+                    $(Feature::$feature => structs.$structure.$field = vk::TRUE),+
+                }
+            }
+        }
+    };
+}
+
+features!
+{
+    FullDrawIndexUint32 => V1_0, core.full_draw_index_uint32;
+    ImageCubeArray => V1_0, core.image_cube_array;
+    IndependentBlend => V1_0, core.independent_blend;
+    GeometryShader => V1_0, core.geometry_shader;
+    TessellationShader => V1_0, core.tessellation_shader;
+    SampleRateShading => V1_0, core.sample_rate_shading;
+    DualSrcBlend => V1_0, core.dual_src_blend;
+    LogicOp => V1_0, core.logic_op;
+    MultiDrawIndirect => V1_0, core.multi_draw_indirect;
+    DrawIndirectFirstInstance => V1_0, core.draw_indirect_first_instance;
+    DepthClamp => V1_0, core.depth_clamp;
+    DepthBiasClamp => V1_0, core.depth_bias_clamp;
+    FillModeNonSolid => V1_0, core.fill_mode_non_solid;
+    DepthBounds => V1_0, core.depth_bounds;
+    WideLines => V1_0, core.wide_lines;
+    LargePoints => V1_0, core.large_points;
+    AlphaToOne => V1_0, core.alpha_to_one;
+    MultiViewport => V1_0, core.multi_viewport;
+    SamplerAnisotropy => V1_0, core.sampler_anisotropy;
+    TextureCompressionBc => V1_0, core.texture_compression_bc;
+    TextureCompressionEtc2 => V1_0, core.texture_compression_etc2;
+    TextureCompressionAstcLdr => V1_0, core.texture_compression_astc_ldr;
+    OcclusionQueryPrecise => V1_0, core.occlusion_query_precise;
+    PipelineStatisticsQuery => V1_0, core.pipeline_statistics_query;
+    VertexPipelineStoresAndAtomics => V1_0, core.vertex_pipeline_stores_and_atomics;
+    FragmentStoresAndAtomics => V1_0, core.fragment_stores_and_atomics;
+    ShaderFloat64 => V1_0, core.shader_float64;
+    ShaderInt64 => V1_0, core.shader_int64;
+    ShaderInt16 => V1_0, core.shader_int16;
+    Multiview => V1_1, multiview.multiview;
+    ShaderDrawParameters => V1_1, shader_draw_parameters.shader_draw_parameters;
+    StorageBuffer16BitAccess => V1_1, storage_16bit.storage_buffer16_bit_access;
+    DrawIndirectCount => V1_2, vulkan12.draw_indirect_count;
+    StorageBuffer8BitAccess => V1_2, vulkan12.storage_buffer8_bit_access;
+    ShaderFloat16 => V1_2, vulkan12.shader_float16;
+    ShaderInt8 => V1_2, vulkan12.shader_int8;
+    DescriptorIndexing => V1_2, vulkan12.descriptor_indexing;
+    RuntimeDescriptorArray => V1_2, vulkan12.runtime_descriptor_array;
+    SamplerFilterMinmax => V1_2, vulkan12.sampler_filter_minmax;
+    ScalarBlockLayout => V1_2, vulkan12.scalar_block_layout;
+    UniformBufferStandardLayout => V1_2, vulkan12.uniform_buffer_standard_layout;
+    HostQueryReset => V1_2, vulkan12.host_query_reset;
+    TimelineSemaphore => V1_2, vulkan12.timeline_semaphore;
+    BufferDeviceAddress => V1_2, vulkan12.buffer_device_address;
+    VulkanMemoryModel => V1_2, vulkan12.vulkan_memory_model;
+    ShaderDemoteToHelperInvocation => V1_3, vulkan13.shader_demote_to_helper_invocation;
+    Synchronization2 => V1_3, vulkan13.synchronization2;
+    DynamicRendering => V1_3, vulkan13.dynamic_rendering;
+    Maintenance4 => V1_3, vulkan13.maintenance4;
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DeviceFeatures
+{
+    pub required: Vec<Feature>,
+    pub optional: Vec<Feature>
+}
+
+impl DeviceFeatures
+{
+    pub fn empty() -> Self
+    {
+        Self { required: Vec::new(), optional: Vec::new() }
+    }
+
+    //the required features plus those the enabled crate features depend on
+    pub(crate) fn required_with_crate_features(&self) -> Vec<Feature>
+    {
+        #[allow(unused_mut)] let mut required = self.required.clone();
+        #[cfg(feature = "multiview")]
+        if !required.contains(&Feature::Multiview) { required.push(Feature::Multiview); }
+        required
+    }
+}
+
+impl Default for DeviceFeatures
+{
+    //the features gru-vulkan used to enable implicitly
+    fn default() -> Self
+    {
+        Self
+        {
+            required: Vec::new(),
+            optional: vec![Feature::SamplerAnisotropy, Feature::FillModeNonSolid, Feature::WideLines, Feature::SampleRateShading, Feature::DepthClamp]
+        }
+    }
+}
+
+//all feature structs gru-vulkan knows about, the pointer chain is built on demand
+#[derive(Default)]
+pub(crate) struct FeatureStructs
+{
+    pub(crate) core: vk::PhysicalDeviceFeatures,
+    pub(crate) multiview: vk::PhysicalDeviceMultiviewFeatures<'static>,
+    pub(crate) shader_draw_parameters: vk::PhysicalDeviceShaderDrawParametersFeatures<'static>,
+    pub(crate) storage_16bit: vk::PhysicalDevice16BitStorageFeatures<'static>,
+    pub(crate) vulkan12: vk::PhysicalDeviceVulkan12Features<'static>,
+    pub(crate) vulkan13: vk::PhysicalDeviceVulkan13Features<'static>
+}
+
+impl FeatureStructs
+{
+    pub(crate) fn from_features(features: &[Feature]) -> Self
+    {
+        let mut structs = Self::default();
+        for feature in features { feature.set(&mut structs); }
+        structs
+    }
+
+    pub(crate) fn supports(&self, feature: Feature) -> bool
+    {
+        feature.get(self)
+    }
+
+    pub(crate) fn query(instance: &Instance, physical_device: vk::PhysicalDevice, api_version: ApiVersion) -> Self
+    {
+        let mut structs = Self { core: unsafe { instance.0.instance.get_physical_device_features(physical_device) }, ..Default::default() };
+        if api_version >= ApiVersion::V1_1 || instance.0.properties2
+        {
+            let mut features2 = vk::PhysicalDeviceFeatures2::default().push_next(&mut structs.multiview);
+            if api_version >= ApiVersion::V1_1 { features2 = features2.push_next(&mut structs.shader_draw_parameters).push_next(&mut structs.storage_16bit); }
+            if api_version >= ApiVersion::V1_2 { features2 = features2.push_next(&mut structs.vulkan12); }
+            if api_version >= ApiVersion::V1_3 { features2 = features2.push_next(&mut structs.vulkan13); }
+            if api_version >= ApiVersion::V1_1 { unsafe { instance.0.instance.get_physical_device_features2(physical_device, &mut features2) }; }
+            else
+            {
+                let properties2_instance = ash::khr::get_physical_device_properties2::Instance::new(&instance.0.entry, &instance.0.instance);
+                unsafe { properties2_instance.get_physical_device_features2(physical_device, &mut features2) };
+            }
+        }
+        structs.unchain();
+        structs
+    }
+
+    //the queried structs point to each other, this must not leak into device creation
+    fn unchain(&mut self)
+    {
+        self.multiview.p_next = std::ptr::null_mut();
+        self.shader_draw_parameters.p_next = std::ptr::null_mut();
+        self.storage_16bit.p_next = std::ptr::null_mut();
+        self.vulkan12.p_next = std::ptr::null_mut();
+        self.vulkan13.p_next = std::ptr::null_mut();
+    }
+}
@@ -5,9 +5,15 @@ use super::*;
 
 impl Device
 {
-	pub fn new_image(&self, image_type: ImageType, image_usage: ImageUsage) -> Image
+	pub fn new_image(&self, image_type: ImageType, image_usage: ImageUsage) -> Result<Image, Error>
+    {
+        if DEBUG_MODE && image_usage.depth() && !image_type.channel.has_depth() { return Err(Error::Misuse(String::from("Device::new_image: This ImageChannelType has no depth component."))); }
+        self.new_image_with_flags(image_type, image_usage, image_usage.vk_image_usage_flags())
+    }
+
+    //for images whose usage cannot be expressed by ImageUsage
+    pub(crate) fn new_image_with_flags(&self, image_type: ImageType, image_usage: ImageUsage, usage_flags: vk::ImageUsageFlags) -> Result<Image, Error>
     {
-        if DEBUG_MODE && image_usage.depth() && !image_type.channel.has_depth() { panic!("Device::new_image: This ImageChannelType has no depth component."); }
         let mip_levels = image_usage.mip_levels(image_type);
         let image_create_info = vk::ImageCreateInfo::default()
             .image_type(vk::ImageType::TYPE_2D)
@@ -23,11 +29,11 @@ impl Device
             .tiling(vk::ImageTiling::OPTIMAL)
             .sharing_mode(vk::SharingMode::EXCLUSIVE)
             .samples(image_usage.vk_sample_count())
-            .usage(image_usage.vk_image_usage_flags())
+            .usage(usage_flags)
             .flags(image_type.flags());
 
         let device = &self.0.logical_device;
-        let vk_image = unsafe { device.create_image(&image_create_info, None) }.unwrap();
+        let vk_image = unsafe { device.create_image(&image_create_info, None) }?;
         let allocation_create_desc = alloc::AllocationCreateDesc
         {
             name: "",
@@ -36,8 +42,7 @@ impl Device
             linear: false,
             allocation_scheme: alloc::AllocationScheme::GpuAllocatorManaged
         };
-        let allocation = self.0.allocator.as_ref().unwrap().lock().unwrap().allocate(&allocation_create_desc).unwrap();
-        unsafe { device.bind_image_memory(vk_image, allocation.memory(), allocation.offset()).unwrap(); }
+        let allocation = self.0.allocate_image_memory(vk_image, &allocation_create_desc)?;
 
         let image_view_create_info = vk::ImageViewCreateInfo::default()
             .image(vk_image)
@@ -50,9 +55,18 @@ impl Device
                 layer_count: image_type.layers(),
                 ..Default::default()
             });
-        let image_view = unsafe { self.0.logical_device.create_image_view(&image_view_create_info, None) }.unwrap();
+        let image_view = match unsafe { self.0.logical_device.create_image_view(&image_view_create_info, None) }
+        {
+            Ok(image_view) => image_view,
+            Err(error) =>
+            {
+                unsafe { device.destroy_image(vk_image, None); }
+                self.0.allocator.as_ref().unwrap().lock().unwrap().free(allocation)?;
+                return Err(error.into());
+            }
+        };
 
-        Image
+        Ok(Image
         {
             device: self.0.clone(),
             allocation: Some(allocation),
@@ -61,16 +75,16 @@ impl Device
             image_type,
             mip_levels,
             image_usage
-        }
+        })
     }
 
-    pub fn new_image_buffer(&self, image_type: ImageType) -> ImageBuffer
+    pub fn new_image_buffer(&self, image_type: ImageType) -> Result<ImageBuffer, Error>
     {
         let buffer_create_info = vk::BufferCreateInfo::default()
             .size(image_type.layer_size_in_bytes())
             .usage(vk::BufferUsageFlags::TRANSFER_SRC | vk::BufferUsageFlags::TRANSFER_DST);
         let device = &self.0.logical_device;
-        let buffer = unsafe { device.create_buffer(&buffer_create_info, None) }.unwrap();
+        let buffer = unsafe { device.create_buffer(&buffer_create_info, None) }?;
         let allocation_create_desc = alloc::AllocationCreateDesc
         {
             name: "",
@@ -79,20 +93,20 @@ impl Device
             linear: true,
             allocation_scheme: alloc::AllocationScheme::GpuAllocatorManaged
         };
-        let allocation = self.0.allocator.as_ref().unwrap().lock().unwrap().allocate(&allocation_create_desc).unwrap();
-        unsafe { device.bind_buffer_memory(buffer, allocation.memory(), allocation.offset()).unwrap(); }
+        let allocation = self.0.allocate_buffer_memory(buffer, &allocation_create_desc)?;
 
-        ImageBuffer
+        Ok(ImageBuffer
         {
             device: self.0.clone(),
             allocation: Some(allocation),
             buffer,
             image_type
-        }
+        })
     }
 
-    pub fn new_sampler(&self, info: SamplerInfo) -> Sampler
+    pub fn new_sampler(&self, info: SamplerInfo) -> Result<Sampler, Error>
     {
+        if DEBUG_MODE && info.anisotropy && !self.feature_enabled(Feature::SamplerAnisotropy) { return Err(Error::Misuse(String::from("Device::new_sampler: Anisotropy requires Feature::SamplerAnisotropy."))); }
         let sampler_info = vk::SamplerCreateInfo::default()
             .mag_filter(info.mag_filter.vk_filter())
             .min_filter(info.min_filter.vk_filter())
@@ -107,8 +121,8 @@ impl Device
             .max_anisotropy(16.0)
             .unnormalized_coordinates(false)
             .compare_enable(false);
-        let sampler = unsafe { self.0.logical_device.create_sampler(&sampler_info, None) }.unwrap();
-        Sampler { device: self.0.clone(), sampler }
+        let sampler = unsafe { self.0.logical_device.create_sampler(&sampler_info, None) }?;
+        Ok(Sampler { device: self.0.clone(), sampler })
     }
 }
 
@@ -146,48 +160,8 @@ impl CommandBuffer
             { panic!("CommandBuffer::copy_image: This queue family does not support graphic transfer operations."); }
         if DEBUG_MODE && src.image_type != dst.image_type { panic!("CommandBuffer::copy_image: Buffer and image need to have the same image_type."); }
         if DEBUG_MODE && layer >= dst.image_type.layers() { panic!("CommandBuffer::copy_image: Layer too large."); }
-        let image_type = src.image_type;
-
         let command_buffer_begin_info = vk::CommandBufferBeginInfo::default()
             .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
-        let mut barrier = vk::ImageMemoryBarrier::default()
-            .image(dst.image)
-            .src_access_mask(vk::AccessFlags::empty())
-            .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE)
-            .old_layout(vk::ImageLayout::UNDEFINED)
-            .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-            .subresource_range(vk::ImageSubresourceRange
-            {
-                aspect_mask: vk::ImageAspectFlags::COLOR,
-                base_mip_level: 0,
-                level_count: dst.mip_levels,
-                base_array_layer: layer,
-                layer_count: 1,
-            })
-            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED);
-        let image_subresource = vk::ImageSubresourceLayers
-        {
-            aspect_mask: vk::ImageAspectFlags::COLOR,
-            mip_level: 0,
-            base_array_layer: layer,
-            layer_count: 1,
-        };
-        let region = vk::BufferImageCopy
-        {
-            buffer_offset: 0,
-            buffer_row_length: 0,
-            buffer_image_height: 0,
-            image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
-            image_extent: vk::Extent3D
-            {
-                width: image_type.width,
-                height: image_type.height,
-                depth: 1,
-            },
-            image_subresource,
-            ..Default::default()
-        };
         let submit_info =
         [
             vk::SubmitInfo::default()
@@ -196,60 +170,7 @@ impl CommandBuffer
         unsafe
         {
             self.pool.device.logical_device.begin_command_buffer(self.command_buffer, &command_buffer_begin_info).unwrap();
-            self.pool.device.logical_device.cmd_pipeline_barrier(self.command_buffer, vk::PipelineStageFlags::TOP_OF_PIPE, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
-            self.pool.device.logical_device.cmd_copy_buffer_to_image(self.command_buffer, src.buffer, dst.image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[region]);
-            let mut mip_width = dst.image_type.width;
-            let mut mip_height = dst.image_type.height;
-            barrier.subresource_range.level_count = 1;
-            for i in 1..dst.mip_levels
-            {
-                barrier.subresource_range.base_mip_level = i - 1;
-                barrier.src_access_mask = vk::AccessFlags::TRANSFER_WRITE;
-                barrier.dst_access_mask = vk::AccessFlags::TRANSFER_READ;
-                barrier.old_layout = vk::ImageLayout::TRANSFER_DST_OPTIMAL;
-                barrier.new_layout = vk::ImageLayout::TRANSFER_SRC_OPTIMAL;
-                self.pool.device.logical_device.cmd_pipeline_barrier(self.command_buffer, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
-                let image_blit = vk::ImageBlit::default()
-                    .src_offsets(
-                    [
-                        vk::Offset3D { x: 0, y: 0, z: 0 },
-                        vk::Offset3D { x: mip_width as i32, y: mip_height as i32, z: 1 }
-                    ])
-                    .src_subresource(vk::ImageSubresourceLayers
-                    {
-                        aspect_mask: vk::ImageAspectFlags::COLOR,
-                        mip_level: i - 1,
-                        base_array_layer: layer,
-                        layer_count: 1
-                    })
-                    .dst_offsets(
-                    [
-                        vk::Offset3D { x: 0, y: 0, z: 0 },
-                        vk::Offset3D { x: if mip_width > 1 { mip_width as i32 / 2 } else { 1 }, y: if mip_height > 1 { mip_height as i32 / 2 } else { 1 }, z: 1 },
-                    ])
-                    .dst_subresource(vk::ImageSubresourceLayers
-                    {
-                        aspect_mask: vk::ImageAspectFlags::COLOR,
-                        mip_level: i,
-                        base_array_layer: layer,
-                        layer_count: 1
-                    });
-                self.pool.device.logical_device.cmd_blit_image(self.command_buffer, dst.image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, dst.image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[image_blit], vk::Filter::LINEAR);
-                barrier.subresource_range.base_mip_level = i - 1;
-                barrier.src_access_mask = vk::AccessFlags::TRANSFER_READ;
-                barrier.dst_access_mask = vk::AccessFlags::SHADER_READ;
-                barrier.old_layout = vk::ImageLayout::TRANSFER_SRC_OPTIMAL;
-                barrier.new_layout = vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
-                self.pool.device.logical_device.cmd_pipeline_barrier(self.command_buffer, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
-                if mip_width > 1 { mip_width /= 2; }
-                if mip_height > 1 { mip_height /= 2; }
-            }
-            barrier.subresource_range.base_mip_level = dst.mip_levels - 1;
-            barrier.old_layout = vk::ImageLayout::TRANSFER_DST_OPTIMAL;
-            barrier.new_layout = vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
-            barrier.src_access_mask = vk::AccessFlags::TRANSFER_WRITE;
-            barrier.dst_access_mask = vk::AccessFlags::SHADER_READ;
-            self.pool.device.logical_device.cmd_pipeline_barrier(self.command_buffer, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
+            record_image_upload(&self.pool.device, self.command_buffer, src.buffer, 0, dst, layer);
             self.pool.device.logical_device.end_command_buffer(self.command_buffer).unwrap();
             self.pool.device.logical_device.queue_submit(queue.queue, &submit_info, mark.fence).unwrap();
         }
@@ -261,7 +182,7 @@ impl CommandBuffer
         let (legal, image, image_type, layout) = match src
         {
             CopyImageSource::Swapchain(image) =>
-                (true, image.image, ImageType { channel: Swapchain::IMAGE_CHANNEL_TYPE, width: image.width, height: image.height, layers: ImageLayers::Single }, vk::ImageLayout::PRESENT_SRC_KHR),
+                (image.usage.transfer_src, image.image, ImageType { channel: image.format.image_channel_type(), width: image.width, height: image.height, layers: ImageLayers::Single }, self.pool.device.present_layout()),
             CopyImageSource::Image(image) =>
                 if let ImageUsage::Attachment { transfer_src: true, .. } = image.image_usage { (true, &image.image, image.image_type, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL) }
                 else { (false, &image.image, image.image_type, vk::ImageLayout::UNDEFINED) }
@@ -338,6 +259,106 @@ impl CommandBuffer
     }
 }
 
+//layout transition, copy of one layer and mip generation, leaves the image in SHADER_READ_ONLY_OPTIMAL
+pub(crate) fn record_image_upload(device: &RawDevice, command_buffer: vk::CommandBuffer, src_buffer: vk::Buffer, buffer_offset: u64, dst: &Image, layer: u32)
+{
+    let image_type = dst.image_type;
+    let mut barrier = vk::ImageMemoryBarrier::default()
+        .image(dst.image)
+        .src_access_mask(vk::AccessFlags::empty())
+        .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+        .old_layout(vk::ImageLayout::UNDEFINED)
+        .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .subresource_range(vk::ImageSubresourceRange
+        {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count: dst.mip_levels,
+            base_array_layer: layer,
+            layer_count: 1,
+        })
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED);
+    let image_subresource = vk::ImageSubresourceLayers
+    {
+        aspect_mask: vk::ImageAspectFlags::COLOR,
+        mip_level: 0,
+        base_array_layer: layer,
+        layer_count: 1,
+    };
+    let region = vk::BufferImageCopy
+    {
+        buffer_offset,
+        buffer_row_length: 0,
+        buffer_image_height: 0,
+        image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+        image_extent: vk::Extent3D
+        {
+            width: image_type.width,
+            height: image_type.height,
+            depth: 1,
+        },
+        image_subresource
+    };
+    unsafe
+    {
+        device.logical_device.cmd_pipeline_barrier(command_buffer, vk::PipelineStageFlags::TOP_OF_PIPE, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
+        device.logical_device.cmd_copy_buffer_to_image(command_buffer, src_buffer, dst.image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[region]);
+        let mut mip_width = dst.image_type.width;
+        let mut mip_height = dst.image_type.height;
+        barrier.subresource_range.level_count = 1;
+        for i in 1..dst.mip_levels
+        {
+            barrier.subresource_range.base_mip_level = i - 1;
+            barrier.src_access_mask = vk::AccessFlags::TRANSFER_WRITE;
+            barrier.dst_access_mask = vk::AccessFlags::TRANSFER_READ;
+            barrier.old_layout = vk::ImageLayout::TRANSFER_DST_OPTIMAL;
+            barrier.new_layout = vk::ImageLayout::TRANSFER_SRC_OPTIMAL;
+            device.logical_device.cmd_pipeline_barrier(command_buffer, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
+            let image_blit = vk::ImageBlit::default()
+                .src_offsets(
+                [
+                    vk::Offset3D { x: 0, y: 0, z: 0 },
+                    vk::Offset3D { x: mip_width as i32, y: mip_height as i32, z: 1 }
+                ])
+                .src_subresource(vk::ImageSubresourceLayers
+                {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: i - 1,
+                    base_array_layer: layer,
+                    layer_count: 1
+                })
+                .dst_offsets(
+                [
+                    vk::Offset3D { x: 0, y: 0, z: 0 },
+                    vk::Offset3D { x: if mip_width > 1 { mip_width as i32 / 2 } else { 1 }, y: if mip_height > 1 { mip_height as i32 / 2 } else { 1 }, z: 1 },
+                ])
+                .dst_subresource(vk::ImageSubresourceLayers
+                {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: i,
+                    base_array_layer: layer,
+                    layer_count: 1
+                });
+            device.logical_device.cmd_blit_image(command_buffer, dst.image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, dst.image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[image_blit], vk::Filter::LINEAR);
+            barrier.subresource_range.base_mip_level = i - 1;
+            barrier.src_access_mask = vk::AccessFlags::TRANSFER_READ;
+            barrier.dst_access_mask = vk::AccessFlags::SHADER_READ;
+            barrier.old_layout = vk::ImageLayout::TRANSFER_SRC_OPTIMAL;
+            barrier.new_layout = vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+            device.logical_device.cmd_pipeline_barrier(command_buffer, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
+            if mip_width > 1 { mip_width /= 2; }
+            if mip_height > 1 { mip_height /= 2; }
+        }
+        barrier.subresource_range.base_mip_level = dst.mip_levels - 1;
+        barrier.old_layout = vk::ImageLayout::TRANSFER_DST_OPTIMAL;
+        barrier.new_layout = vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+        barrier.src_access_mask = vk::AccessFlags::TRANSFER_WRITE;
+        barrier.dst_access_mask = vk::AccessFlags::SHADER_READ;
+        device.logical_device.cmd_pipeline_barrier(command_buffer, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum CopyImageSource<'a>
 {
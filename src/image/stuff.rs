@@ -13,8 +13,29 @@ impl ImageLayout
     }
 }
 
+const _: () =
+{
+    let mut index = 0;
+    while index < ImageChannelType::ALL.len()
+    {
+        assert!(ImageChannelType::ALL[index].index() == index, "ImageChannelType::ALL must list the variants in declaration order");
+        index += 1;
+    }
+};
+
 impl ImageChannelType
 {
+    pub const ALL: &'static [ImageChannelType] =
+    &[
+        Self::BgraSrgb, Self::BgraSnorm, Self::BgraUnorm, Self::BgraSint, Self::BgraUint,
+        Self::RgbaSfloat, Self::RgbaShalffloat, Self::A2Bgr10Unorm,
+        Self::RSrgb, Self::RSnorm, Self::RUnorm, Self::RSint, Self::RUint, Self::R32Uint, Self::RSfloat,
+        Self::DSfloat
+    ];
+
+    //position in ALL, which lists the variants in declaration order
+    pub(crate) const fn index(&self) -> usize { *self as usize }
+
 	pub(crate) const fn size_in_bytes(&self) -> u32
 	{
 		match self
@@ -26,6 +47,7 @@ impl ImageChannelType
             Self::BgraUint => 4,
             Self::RgbaSfloat => 32,
             Self::RgbaShalffloat => 16,
+            Self::A2Bgr10Unorm => 4,
             Self::RSrgb => 1,
             Self::RSnorm => 1,
             Self::RUnorm => 1,
@@ -48,6 +70,7 @@ impl ImageChannelType
             Self::BgraUint => vk::Format::B8G8R8A8_UINT,
             Self::RgbaSfloat => vk::Format::R32G32B32A32_SFLOAT,
             Self::RgbaShalffloat => vk::Format::R16G16B16A16_SFLOAT,
+            Self::A2Bgr10Unorm => vk::Format::A2B10G10R10_UNORM_PACK32,
             Self::RSrgb => vk::Format::R8_SRGB,
             Self::RSnorm => vk::Format::R8_SNORM,
             Self::RUnorm => vk::Format::R8_UNORM,
@@ -1,149 +1,414 @@
 use super::*;
 use std::os::raw::c_char;
 
-fn layer_name_pointers(entry: &ash::Entry) -> (Vec<std::ffi::CString>, Vec<*const c_char>)
+const VALIDATION_LAYER: &std::ffi::CStr = c"VK_LAYER_KHRONOS_validation";
+
+fn layer_name_pointers(entry: &ash::Entry, layer_names: &[std::ffi::CString]) -> Result<(Vec<*const c_char>, Vec<String>), Error>
 {
-    let available_layers = unsafe { entry.enumerate_instance_layer_properties() }.unwrap();
-    let layer_names: Vec<std::ffi::CString> =
-        if DEBUG_MODE { vec![std::ffi::CString::from(c"VK_LAYER_KHRONOS_validation")] }
-        else { vec![] };
-    let layer_name_pointers = layer_names.iter()
-        .filter(|name| available_layers.iter().any(|available| unsafe
+    let available_layers = unsafe { entry.enumerate_instance_layer_properties() }?;
+    let (mut layer_name_pointers, mut missing) = (Vec::new(), Vec::new());
+    for name in layer_names
+    {
+        let available = available_layers.iter().any(|available| unsafe
         {
             let available = std::ffi::CStr::from_ptr(&available.layer_name as *const c_char);
-            let wanted = name.as_c_str();
-            available == wanted
-        }))
-        .map(|layer_name| layer_name.as_ptr())
-        .collect();
-    (layer_names, layer_name_pointers)
+            available == name.as_c_str()
+        });
+        if available { layer_name_pointers.push(name.as_ptr()); }
+        else { missing.push(name.to_string_lossy().into_owned()); }
+    }
+    Ok((layer_name_pointers, missing))
 }
 
-fn extension_name_pointers(entry: &ash::Entry, window: Option<&dyn HasBothHandles>) -> (Vec<*const c_char>, bool)
+fn extension_name_pointers(entry: &ash::Entry, builder: &InstanceBuilder) -> Result<(Vec<*const c_char>, Vec<String>, bool, bool), Error>
 {
-    let available_extensions: Vec<_> = unsafe { entry.enumerate_instance_extension_properties(None) }.unwrap().into_iter()
+    let mut available_extensions = unsafe { entry.enumerate_instance_extension_properties(None) }?;
+    //VK_EXT_validation_features is provided by the validation layer
+    if builder.validation { available_extensions.extend(unsafe { entry.enumerate_instance_extension_properties(Some(VALIDATION_LAYER)) }.unwrap_or_default()); }
+    let available_extensions: Vec<_> = available_extensions.into_iter()
         .map(|ext| unsafe { std::ffi::CStr::from_ptr(&ext.extension_name as *const c_char) }.to_owned())
         .collect();
     //for av in&available_extensions { println!("{av:?}"); }
     let exists = move |name: &std::ffi::CStr| available_extensions.iter().any(|available| available.as_c_str() == name);
-    let mut extension_name_pointers = Vec::new();
-    if let Some(window) = window
+    let (mut extension_name_pointers, mut missing) = (Vec::<*const c_char>::new(), Vec::new());
+    //optional extensions are not reported as missing
+    let mut request = |name: &std::ffi::CStr, optional: bool|
     {
-        ash_window::enumerate_required_extensions(window.display_handle().unwrap().as_raw()).unwrap().iter().for_each(|extension|
+        if extension_name_pointers.iter().any(|pointer| unsafe { std::ffi::CStr::from_ptr(*pointer) } == name) { return true; }
+        let available = exists(name);
+        if available { extension_name_pointers.push(name.as_ptr()); }
+        else if !optional { missing.push(name.to_string_lossy().into_owned()); }
+        available
+    };
+    if let Some(window) = builder.window
+    {
+        for extension in ash_window::enumerate_required_extensions(window.display_handle()?.as_raw())?
         {
-            let name = unsafe { std::ffi::CStr::from_ptr(*extension as *const c_char) }.to_owned();
-            if exists(&name) { extension_name_pointers.push(*extension); }
-            else { println!("Instance::new: Extension {name:?} missing"); }
-        });
+            request(unsafe { std::ffi::CStr::from_ptr(*extension) }, false);
+        }
     }
+    if builder.headless
+    {
+        request(ash::khr::surface::NAME, false);
+        request(ash::ext::headless_surface::NAME, false);
+    }
+    //needed for the HDR color spaces of SwapchainFormat
+    if builder.window.is_some() || builder.headless { request(ash::ext::swapchain_colorspace::NAME, true); }
     #[cfg(feature = "multiview")]
+    if builder.api_version == ApiVersion::V1_0 { request(ash::khr::get_physical_device_properties2::NAME, false); }
+    for extension in &builder.extensions { request(extension, false); }
+    let debug = builder.validation && request(ash::ext::debug_utils::NAME, false);
+    let validation_features = builder.validation && builder.validation_features != ValidationFeatures::default() && request(ash::ext::validation_features::NAME, false);
+    Ok((extension_name_pointers, missing, debug, validation_features))
+}
+
+impl RawInstance
+{
+    fn extension_enabled(&self, name: &std::ffi::CStr) -> bool
     {
-        let name = ash::khr::get_physical_device_properties2::NAME;
-        if exists(name) { extension_name_pointers.push(name.as_ptr()); }
-        else { println!("Instance::new: Extension {name:?} missing"); }
+        self.extensions.iter().any(|extension| extension.as_c_str() == name)
     }
+}
 
-    let mut debug = false;
-    if DEBUG_MODE
+impl Surface
+{
+    pub(crate) fn new(instance: &Arc<RawInstance>, window: &dyn HasBothHandles) -> Result<Self, Error>
     {
-        let name = ash::ext::debug_utils::NAME;
-        if exists(&name.to_owned())
+        let display = window.display_handle()?.as_raw();
+        let required = ash_window::enumerate_required_extensions(display)?;
+        if !required.iter().all(|extension| instance.extension_enabled(unsafe { std::ffi::CStr::from_ptr(*extension) }))
         {
-            extension_name_pointers.push(name.as_ptr());
-            debug = true;
+            return Err(Error::Misuse(String::from("Surface::new: The instance was built without the surface extensions of this window.")));
         }
+        let surface = unsafe { ash_window::create_surface(&instance.entry, &instance.instance, display, window.window_handle()?.as_raw(), None) }?;
+        let loader = ash::khr::surface::Instance::new(&instance.entry, &instance.instance);
+        Ok(Surface(Arc::new(RawSurface { instance: instance.clone(), loader, surface, extent: None })))
     }
-    (extension_name_pointers, debug)
-}
 
-fn surface(entry: &ash::Entry, instance: &ash::Instance, window: &dyn HasBothHandles) -> vk::SurfaceKHR
-{
-    unsafe { ash_window::create_surface(entry, instance, window.display_handle().unwrap().as_raw(), window.window_handle().unwrap().as_raw(), None) }.unwrap()
+    pub(crate) fn new_headless(instance: &Arc<RawInstance>, width: u32, height: u32) -> Result<Self, Error>
+    {
+        if !instance.extension_enabled(ash::ext::headless_surface::NAME) { return Err(Error::UnsupportedFeature); }
+        let headless_loader = ash::ext::headless_surface::Instance::new(&instance.entry, &instance.instance);
+        let surface = unsafe { headless_loader.create_headless_surface(&vk::HeadlessSurfaceCreateInfoEXT::default(), None) }?;
+        let loader = ash::khr::surface::Instance::new(&instance.entry, &instance.instance);
+        Ok(Surface(Arc::new(RawSurface { instance: instance.clone(), loader, surface, extent: Some((width, height)) })))
+    }
+
+    pub(crate) fn supports(&self, physical_device: vk::PhysicalDevice, queue_family_index: usize) -> Result<bool, Error>
+    {
+        Ok(unsafe { self.0.loader.get_physical_device_surface_support(physical_device, queue_family_index as u32, self.0.surface) }?)
+    }
+
+    pub fn is_headless(&self) -> bool { self.0.extent.is_some() }
 }
 
 pub unsafe trait HasBothHandles: raw_window_handle::HasDisplayHandle + raw_window_handle::HasWindowHandle {}
 unsafe impl<T: raw_window_handle::HasDisplayHandle + raw_window_handle::HasWindowHandle> HasBothHandles for T {}
 
-impl Instance
+impl ApiVersion
+{
+    pub(crate) fn from_vk(version: u32) -> Self
+    {
+        match (vk::api_version_major(version), vk::api_version_minor(version))
+        {
+            (1, 0) => ApiVersion::V1_0,
+            (1, 1) => ApiVersion::V1_1,
+            (1, 2) => ApiVersion::V1_2,
+            _ => ApiVersion::V1_3
+        }
+    }
+
+    pub(crate) const fn vk_version(&self) -> u32
+    {
+        match self
+        {
+            ApiVersion::V1_0 => vk::API_VERSION_1_0,
+            ApiVersion::V1_1 => vk::API_VERSION_1_1,
+            ApiVersion::V1_2 => vk::API_VERSION_1_2,
+            ApiVersion::V1_3 => vk::API_VERSION_1_3
+        }
+    }
+}
+
+impl<'a> InstanceBuilder<'a>
 {
-    pub fn new(window: Option<&dyn HasBothHandles>) -> Self
+    pub fn app_name(mut self, name: &str) -> Self
+    {
+        if let Some(name) = self.c_string(name) { self.app_name = name; }
+        self
+    }
+
+    pub fn app_version(mut self, major: u32, minor: u32, patch: u32) -> Self
     {
+        self.app_version = vk::make_api_version(0, major, minor, patch);
+        self
+    }
+
+    pub fn api_version(mut self, api_version: ApiVersion) -> Self
+    {
+        self.api_version = api_version;
+        self
+    }
+
+    pub fn layer(mut self, name: &str) -> Self
+    {
+        if let Some(name) = self.c_string(name) { self.layers.push(name); }
+        self
+    }
+
+    pub fn extension(mut self, name: &str) -> Self
+    {
+        if let Some(name) = self.c_string(name) { self.extensions.push(name); }
+        self
+    }
+
+    //enables VK_LAYER_KHRONOS_validation and the debug messenger, defaults to debug_assertions
+    pub fn validation(mut self, validation: bool) -> Self
+    {
+        self.validation = validation;
+        self
+    }
+
+    //only has an effect with validation enabled
+    pub fn validation_features(mut self, validation_features: ValidationFeatures) -> Self
+    {
+        self.validation_features = validation_features;
+        self
+    }
+
+    //messages below this severity are not reported, defaults to Verbose
+    pub fn debug_severity(mut self, severity: DebugSeverity) -> Self
+    {
+        self.debug_severity = severity;
+        self
+    }
+
+    pub fn debug_message_types(mut self, message_types: &[DebugMessageType]) -> Self
+    {
+        self.debug_message_types = message_types.to_vec();
+        self
+    }
+
+    pub fn debug_output(mut self, output: DebugOutput) -> Self
+    {
+        self.debug_output = output;
+        self
+    }
+
+    //where the output of debugPrintfEXT goes if ValidationFeatures::debug_printf is enabled
+    pub fn debug_printf_output(mut self, output: DebugPrintfOutput) -> Self
+    {
+        self.debug_printf_output = output;
+        self
+    }
+
+    //enables VK_EXT_headless_surface for Instance::new_headless_surface
+    pub fn headless(mut self, headless: bool) -> Self
+    {
+        self.headless = headless;
+        self
+    }
+
+    //enables the surface extensions of the window's display, surfaces are created with Instance::new_surface
+    pub fn window(mut self, window: &'a dyn HasBothHandles) -> Self
+    {
+        self.window = Some(window);
+        self
+    }
+
+    fn c_string(&mut self, name: &str) -> Option<std::ffi::CString>
+    {
+        let c_string = std::ffi::CString::new(name).ok();
+        if c_string.is_none() && self.invalid_name.is_none() { self.invalid_name = Some(String::from(name)); }
+        c_string
+    }
+
+    pub fn build(self) -> Result<Instance, Error>
+    {
+        if let Some(name) = &self.invalid_name { return Err(Error::Misuse(format!("InstanceBuilder::build: The name {:?} contains a nul byte.", name))); }
+        if self.validation_features.gpu_assisted && self.validation_features.debug_printf { return Err(Error::Misuse(String::from("InstanceBuilder::build: ValidationFeatures::gpu_assisted cannot be combined with debug_printf."))); }
         #[cfg(feature = "linked")]
         #[allow(unused)]
         let entry = ash::Entry::linked();
         #[cfg(feature = "loaded")]
         #[allow(unused)]
-        let entry = unsafe { ash::Entry::load() }.unwrap();
+        let entry = unsafe { ash::Entry::load() }?;
         #[cfg(not(any(feature = "linked", feature = "loaded")))]
         #[allow(unused)]
         let entry = std::compile_error!("Enable either the \"linked\" or the \"loaded\" feature!");
        
         let enginename = std::ffi::CString::new("gru-vulkan").unwrap();
-        let app_name = std::ffi::CString::new("osyfe app").unwrap();
         let app_info = vk::ApplicationInfo::default()
-            .application_name(&app_name)
-            .application_version(vk::make_api_version(0, 0, 0, 1))
+            .application_name(&self.app_name)
+            .application_version(self.app_version)
             .engine_name(&enginename)
             .engine_version(vk::make_api_version(0, 0, 0, 1))
-            .api_version(vk::make_api_version(0, 1, 0, 299));
-            
-        let (_layer_names, layer_name_pointers) = layer_name_pointers(&entry);
-        let (extension_name_pointers, debug_ext) = extension_name_pointers(&entry, window);
-        let instance_create_info = vk::InstanceCreateInfo::default()
+            .api_version(self.api_version.vk_version());
+
+        let mut layer_names = self.layers.clone();
+        if self.validation && !layer_names.iter().any(|name| name.as_c_str() == VALIDATION_LAYER) { layer_names.push(VALIDATION_LAYER.to_owned()); }
+        let (layer_name_pointers, missing_layers) = layer_name_pointers(&entry, &layer_names)?;
+        let (extension_name_pointers, missing_extensions, debug_ext, validation_features_ext) = extension_name_pointers(&entry, &self)?;
+        let mut instance_create_info = vk::InstanceCreateInfo::default()
             .application_info(&app_info)
             .enabled_layer_names(&layer_name_pointers)
             .enabled_extension_names(&extension_name_pointers);
-            
+        let enabled_validation_features = self.validation_features.vk_features();
+        let mut validation_features = vk::ValidationFeaturesEXT::default().enabled_validation_features(&enabled_validation_features);
+        if validation_features_ext { instance_create_info = instance_create_info.push_next(&mut validation_features); }
+
+        let debug_printf = validation_features_ext && self.validation_features.debug_printf;
+        let debug_state = Box::new(DebugState
+        {
+            severity: self.debug_severity,
+            output: self.debug_output,
+            printf_output: self.debug_printf_output,
+            captured: Mutex::new(Vec::new()),
+            error: Mutex::new(None)
+        });
         let (instance, debug_utils) = if debug_ext
         {   
-            let (instance, debug_utils) = debug::create_instance(&entry, instance_create_info);
+            let (instance, debug_utils) = debug::create_instance(&entry, instance_create_info, &self.debug_message_types, debug_printf, &debug_state)?;
             (instance, Some(debug_utils))
         } else
         {
-            let instance = unsafe { entry.create_instance(&instance_create_info, None).unwrap() };
+            let instance = unsafe { entry.create_instance(&instance_create_info, None) }?;
             (instance, None)
         };
         
-        let surface = window.map(|window|
+        let properties2 = cfg!(feature = "multiview") && self.api_version == ApiVersion::V1_0
+            && !missing_extensions.iter().any(|name| name.as_bytes() == ash::khr::get_physical_device_properties2::NAME.to_bytes());
+        let extensions = extension_name_pointers.iter().map(|name| unsafe { std::ffi::CStr::from_ptr(*name) }.to_owned()).collect();
+        Ok(Instance(Arc::new(RawInstance
         {
-            let loader = ash::khr::surface::Instance::new(&entry, &instance);
-            let surface = surface(&entry, &instance, window);
-            Surface { loader, surface }
-        });
-        
-        Self { entry, debug: debug_utils, instance, surface }
+            entry,
+            debug: debug_utils,
+            debug_state,
+            debug_printf,
+            instance,
+            extensions,
+            api_version: self.api_version,
+            properties2,
+            missing_layers: missing_layers.into_boxed_slice(),
+            missing_extensions: missing_extensions.into_boxed_slice()
+        })))
+    }
+}
+
+//score, device and the queue family indices of graphics, async compute and dedicated transfer
+type DeviceCandidate = (u32, PhysicalDevice, usize, Option<usize>, Option<usize>);
+
+impl Instance
+{
+    pub fn builder<'a>() -> InstanceBuilder<'a>
+    {
+        InstanceBuilder
+        {
+            app_name: std::ffi::CString::from(c"osyfe app"),
+            app_version: vk::make_api_version(0, 0, 0, 1),
+            api_version: ApiVersion::V1_0,
+            layers: Vec::new(),
+            extensions: Vec::new(),
+            validation: DEBUG_MODE,
+            headless: false,
+            validation_features: ValidationFeatures::default(),
+            debug_severity: DebugSeverity::Verbose,
+            debug_message_types: vec![DebugMessageType::General, DebugMessageType::Validation, DebugMessageType::Performance],
+            debug_output: DebugOutput::Print,
+            debug_printf_output: DebugPrintfOutput::Print,
+            window: None,
+            invalid_name: None
+        }
+    }
+
+    pub fn new(window: Option<&dyn HasBothHandles>) -> Result<Self, Error>
+    {
+        match window
+        {
+            Some(window) => Self::builder().window(window).build(),
+            None => Self::builder().build()
+        }
+    }
+
+    pub fn api_version(&self) -> ApiVersion { self.0.api_version }
+    //requested layers (including the validation layer) that are not available
+    pub fn missing_layers(&self) -> &[String] { &self.0.missing_layers }
+    //requested or window-required instance extensions that are not available
+    pub fn missing_extensions(&self) -> &[String] { &self.0.missing_extensions }
+
+    //drains the messages collected with DebugOutput::Capture
+    pub fn take_debug_messages(&self) -> Vec<DebugMessage>
+    {
+        self.0.raise_debug_error();
+        std::mem::take(&mut *self.0.debug_state.captured.lock().unwrap())
+    }
+
+    //the instance must have been built with a window of the same display
+    pub fn new_surface(&self, window: &dyn HasBothHandles) -> Result<Surface, Error>
+    {
+        Surface::new(&self.0, window)
+    }
+
+    //needs InstanceBuilder::headless, the extent is used for swapchains on this surface
+    pub fn new_headless_surface(&self, width: u32, height: u32) -> Result<Surface, Error>
+    {
+        Surface::new_headless(&self.0, width, height)
     }
     
-    pub fn physical_devices(&self) -> Vec<PhysicalDevice>
+    pub fn physical_devices(&self) -> Result<Vec<PhysicalDevice>, Error>
     {
-        let physical_devices = unsafe { self.instance.enumerate_physical_devices() }.unwrap();
+        let physical_devices = unsafe { self.0.instance.enumerate_physical_devices() }?;
         physical_devices.iter().map(|physical_device|
         {
-            let physical_device_properties = unsafe { self.instance.get_physical_device_properties(*physical_device) };
-            let queue_family_properties = unsafe { self.instance.get_physical_device_queue_family_properties(*physical_device) }
+            let physical_device_properties = unsafe { self.0.instance.get_physical_device_properties(*physical_device) };
+            let queue_family_properties = unsafe { self.0.instance.get_physical_device_queue_family_properties(*physical_device) }
                 .iter().enumerate().map
                 (|(index, queue_family_properties)| QueueFamilyInfo
                 {
                     index,
-                    queue_family_properties: *queue_family_properties,
-                    surface_support: match &self.surface
-                    {
-                        Some(surface) => unsafe { surface.loader.get_physical_device_surface_support(*physical_device, index as u32, surface.surface) }.unwrap(),
-                        None => false
-                    }
+                    physical_device: *physical_device,
+                    queue_family_properties: *queue_family_properties
                 }).collect();
-            PhysicalDevice
+            let memory_properties = unsafe { self.0.instance.get_physical_device_memory_properties(*physical_device) };
+            let format_properties = std::array::from_fn(|index|
+                unsafe { self.0.instance.get_physical_device_format_properties(*physical_device, ImageChannelType::ALL[index].vk_format()) });
+            let driver = self.driver_info(*physical_device, &physical_device_properties)?;
+            Ok(PhysicalDevice
             {
                 physical_device: *physical_device,
                 physical_device_properties,
+                memory_properties,
+                format_properties,
+                driver,
                 queue_family_properties
-            }
+            })
         }).collect()
     }
     
-    pub fn logical_device<'a, A: AsRef<[f32]>, B: AsRef<[(&'a QueueFamilyInfo, A)]>>(self, physical_device: &PhysicalDevice, queues: B) -> Device
+    //VkPhysicalDeviceDriverProperties is core since 1.2 and needs vkGetPhysicalDeviceProperties2 (core since 1.1)
+    fn driver_info(&self, physical_device: vk::PhysicalDevice, properties: &vk::PhysicalDeviceProperties) -> Result<Option<DriverInfo>, Error>
     {
-        let PhysicalDevice { physical_device, physical_device_properties, queue_family_properties } = physical_device;
+        if self.0.api_version < ApiVersion::V1_1 { return Ok(None); }
+        let supported = ApiVersion::from_vk(properties.api_version) >= ApiVersion::V1_2
+            || unsafe { self.0.instance.enumerate_device_extension_properties(physical_device) }?.iter()
+                .any(|ext| ext.extension_name_as_c_str() == Ok(ash::khr::driver_properties::NAME));
+        if !supported { return Ok(None); }
+        let mut driver_properties = vk::PhysicalDeviceDriverProperties::default();
+        let mut properties2 = vk::PhysicalDeviceProperties2::default().push_next(&mut driver_properties);
+        unsafe { self.0.instance.get_physical_device_properties2(physical_device, &mut properties2); }
+        let to_string = |name: Result<&std::ffi::CStr, _>| name.map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        Ok(Some(DriverInfo
+        {
+            id: DriverId::from_vk(driver_properties.driver_id),
+            name: to_string(driver_properties.driver_name_as_c_str()),
+            info: to_string(driver_properties.driver_info_as_c_str())
+        }))
+    }
+
+    pub fn logical_device<'a, A: AsRef<[f32]>, B: AsRef<[(&'a QueueFamilyInfo, A)]>>(self, physical_device: &PhysicalDevice, queues: B, features: &DeviceFeatures) -> Result<Device, Error>
+    {
+        let supported = self.supported_features(physical_device);
+        let PhysicalDevice { physical_device, physical_device_properties, queue_family_properties, .. } = physical_device;
         let props = Props
         {
             min_uniform_buffer_offset_alignment: physical_device_properties.limits.min_uniform_buffer_offset_alignment,
@@ -155,54 +420,50 @@ impl Instance
         {
             let priorities = priorities.as_ref();
             let index = queue_family_info.index;
-            if priorities.len() == 0 { panic!("Instance::logical_device: No requested queue in queue family {}", index); }
-            if priorities.len() > queue_family_properties[index].count() as usize { panic!("Instance::logical_device: Too many requested queues in queue family {}", index); }
-            if let Some(priority) = priorities.iter().find(|priority| *priority < &0f32 || *priority > &1f32) { panic!("Instance::logical_device: Invalid priority {}", priority); }
-            vk::DeviceQueueCreateInfo::default()
+            if priorities.len() == 0 { return Err(Error::Misuse(format!("Instance::logical_device: No requested queue in queue family {}", index))); }
+            if priorities.len() > queue_family_properties[index].count() as usize { return Err(Error::Misuse(format!("Instance::logical_device: Too many requested queues in queue family {}", index))); }
+            if let Some(priority) = priorities.iter().find(|priority| *priority < &0f32 || *priority > &1f32) { return Err(Error::Misuse(format!("Instance::logical_device: Invalid priority {}", priority))); }
+            Ok(vk::DeviceQueueCreateInfo::default()
                 .queue_family_index(index as u32)
-                .queue_priorities(&priorities)
-        }).collect::<Vec<vk::DeviceQueueCreateInfo>>()[..];
-        #[allow(unused_mut)] let mut device_extension_name_pointers: Vec<*const c_char> = if self.surface.is_some() { vec![ash::khr::swapchain::NAME.as_ptr()] } else { vec![] };
-        #[cfg(feature = "multiview")]
+                .queue_priorities(&priorities))
+        }).collect::<Result<Vec<vk::DeviceQueueCreateInfo>, Error>>()?[..];
+        //features
+        let api_version = self.0.api_version.min(ApiVersion::from_vk(physical_device_properties.api_version));
+        let available = |feature: &Feature| supported.contains(feature);
+        let required = features.required_with_crate_features();
+        let missing: Box<[Feature]> = required.iter().filter(|feature| !available(feature)).copied().collect();
+        if !missing.is_empty() { return Err(Error::MissingFeatures(missing)); }
+        let mut enabled_features: Vec<Feature> = Vec::new();
+        for feature in required.iter().chain(features.optional.iter().filter(|feature| available(feature)))
         {
-            let physical_device_properties2_instance = ash::khr::get_physical_device_properties2::Instance::new(&self.entry, &self.instance);
-
-            let mut physical_device_multiview_features = vk::PhysicalDeviceMultiviewFeaturesKHR::default();
-            let mut physical_device_features2 = vk::PhysicalDeviceFeatures2KHR::default().push_next(&mut physical_device_multiview_features);
-            unsafe { physical_device_properties2_instance.get_physical_device_features2(*physical_device, &mut physical_device_features2)};
-            if physical_device_multiview_features.multiview != 1 { println!("multiview not supported!"); }
-
-            //let mut physical_device_multiview_properties = vk::PhysicalDeviceMultiviewPropertiesKHR::default();
-            //let mut physical_device_properties2 = vk::PhysicalDeviceProperties2KHR::default().push_next(&mut physical_device_multiview_properties);
-            //unsafe { physical_device_properties2_instance.get_physical_device_properties2(*physical_device, &mut physical_device_properties2) };
-            //println!("max multiview count: {}", physical_device_multiview_properties.max_multiview_view_count);
+            if !enabled_features.contains(feature) { enabled_features.push(*feature); }
+        }
+        let mut enabled = FeatureStructs::from_features(&enabled_features);
 
-            device_extension_name_pointers.push(ash::khr::multiview::NAME.as_ptr());
+        let available_extensions: Vec<_> = unsafe { self.0.instance.enumerate_device_extension_properties(*physical_device) }?.into_iter()
+            .map(|ext| unsafe { std::ffi::CStr::from_ptr(&ext.extension_name as *const c_char) }.to_owned())
+            .collect();
+        let extension_available = |name: &std::ffi::CStr| available_extensions.iter().any(|available| available.as_c_str() == name);
+        let swapchain_support = self.0.extension_enabled(ash::khr::surface::NAME) && extension_available(ash::khr::swapchain::NAME);
+        let mut device_extension_name_pointers: Vec<*const c_char> = if swapchain_support { vec![ash::khr::swapchain::NAME.as_ptr()] } else { vec![] };
+        let khr_multiview = api_version < ApiVersion::V1_1 && enabled_features.contains(&Feature::Multiview);
+        if khr_multiview { device_extension_name_pointers.push(ash::khr::multiview::NAME.as_ptr()); }
+        //shaders using debugPrintfEXT need SPV_KHR_non_semantic_info, which is core since 1.3
+        if self.0.debug_printf && api_version < ApiVersion::V1_3 && extension_available(ash::khr::shader_non_semantic_info::NAME)
+        {
+            device_extension_name_pointers.push(ash::khr::shader_non_semantic_info::NAME.as_ptr());
         }
-        let features = unsafe { self.instance.get_physical_device_features(*physical_device) };
-        if features.sampler_anisotropy != 1 { println!("sampler_anisotropy not supported!"); }
-        if features.fill_mode_non_solid != 1 { println!("fill_mode_non_solid not supported!"); }
-        if features.wide_lines != 1 { println!("wide_lines not supported!"); }
-        if features.large_points != 1 { println!("large_points not supported!"); }
-        if features.sample_rate_shading != 1 { println!("sample_rate_shading not supported!"); }
-        if features.depth_clamp != 1 { println!("depth_clamp not supported!"); }
-        let physical_device_features = vk::PhysicalDeviceFeatures::default()
-            .sampler_anisotropy(features.sampler_anisotropy == 1)
-            .fill_mode_non_solid(features.fill_mode_non_solid == 1)
-            .wide_lines(features.wide_lines == 1)
-            .sample_rate_shading(features.sample_rate_shading == 1)
-            .depth_clamp(features.depth_clamp == 1);
-
-        let device_create_info = vk::DeviceCreateInfo::default()
+
+        let mut device_create_info = vk::DeviceCreateInfo::default()
             .queue_create_infos(&queue_infos)
             .enabled_extension_names(&device_extension_name_pointers)
-            .enabled_features(&physical_device_features);
-        #[cfg(feature = "multiview")]
-        let mut physical_device_multiview_features = vk::PhysicalDeviceMultiviewFeaturesKHR::default().multiview(true);
-        #[cfg(feature = "multiview")]
-        let device_create_info = device_create_info.push_next(&mut physical_device_multiview_features);
+            .enabled_features(&enabled.core);
+        if api_version >= ApiVersion::V1_1 || khr_multiview { device_create_info = device_create_info.push_next(&mut enabled.multiview); }
+        if api_version >= ApiVersion::V1_1 { device_create_info = device_create_info.push_next(&mut enabled.shader_draw_parameters).push_next(&mut enabled.storage_16bit); }
+        if api_version >= ApiVersion::V1_2 { device_create_info = device_create_info.push_next(&mut enabled.vulkan12); }
+        if api_version >= ApiVersion::V1_3 { device_create_info = device_create_info.push_next(&mut enabled.vulkan13); }
 
-        let logical_device = unsafe { self.instance.create_device(*physical_device, &device_create_info, None).unwrap() };
+        let logical_device = unsafe { self.0.instance.create_device(*physical_device, &device_create_info, None) }?;
         
         let queue_families = queues.as_ref().iter().map(|(queue_family_info, priorities)|
         {
@@ -212,37 +473,105 @@ impl Instance
             (|(queue_index, _priority)|
                 Arc::new(Mutex::new(Queue { index, queue: unsafe { logical_device.get_device_queue(index as u32, queue_index as u32) } }))
             ).collect();
-            let surface_support = queue_family_properties[index].surface_support;
             let flags = queue_family_properties[index].queue_family_properties.queue_flags.clone();
-            QueueFamily { index, queues, flags, surface_support }
+            QueueFamily { index, queues, flags, physical_device: *physical_device }
         }).collect();
 
         let allocator_create_desc = alloc::AllocatorCreateDesc
         {
-            instance: self.instance.clone(),
+            instance: self.0.instance.clone(),
             device: logical_device.clone(),
             physical_device: *physical_device,
             debug_settings: gpu_allocator::AllocatorDebugSettings::default(),
-            buffer_device_address: false,
+            buffer_device_address: enabled_features.contains(&Feature::BufferDeviceAddress),
             allocation_sizes: Default::default()
         };
-        let allocator = alloc::Allocator::new(&allocator_create_desc).unwrap();
+        let allocator = match alloc::Allocator::new(&allocator_create_desc)
+        {
+            Ok(allocator) => allocator,
+            Err(error) =>
+            {
+                unsafe { logical_device.destroy_device(None); }
+                return Err(error.into());
+            }
+        };
 
         let debug_utils =
-            if DEBUG_MODE { Some(ash::ext::debug_utils::Device::new(&self.instance, &logical_device)) }
+            if self.0.debug.is_some() { Some(ash::ext::debug_utils::Device::new(&self.0.instance, &logical_device)) }
             else { None };
 
-        Device(Arc::new(RawDevice
+        Ok(Device(Arc::new(RawDevice
         {
             instance: self,
             debug_utils,
             physical_device: *physical_device,
             props,
+            features: enabled_features.into_boxed_slice(),
+            swapchain_support,
             logical_device,
             allocator: Some(Mutex::new(allocator)),
             queue_families,
             buffer_layout_count: std::sync::atomic::AtomicU32::new(0)
-        }))
+        })))
+    }
+
+    //features that can be requested from the physical device with the API version of this instance
+    pub fn supported_features(&self, physical_device: &PhysicalDevice) -> Vec<Feature>
+    {
+        let api_version = self.0.api_version.min(physical_device.api_version());
+        let supported = FeatureStructs::query(self, physical_device.physical_device, api_version);
+        Feature::ALL.iter()
+            .filter(|feature| supported.supports(**feature) && (feature.api_version() <= api_version || (**feature == Feature::Multiview && self.0.properties2)))
+            .copied().collect()
+    }
+
+    pub fn auto_device(self, criteria: &DeviceCriteria) -> Result<AutoDevice, Error>
+    {
+        let required = criteria.features.required_with_crate_features();
+        let mut best: Option<DeviceCandidate> = None;
+        for physical_device in self.physical_devices()?
+        {
+            let families = physical_device.queue_families();
+            let mut graphics = None;
+            for (index, family) in families.iter().enumerate()
+            {
+                let present = match criteria.surface
+                {
+                    Some(surface) => family.supports_surface(surface)?,
+                    None => true
+                };
+                if family.supports_graphics() && present { graphics = Some(index); break; }
+            }
+            let graphics = match graphics
+            {
+                Some(graphics) => graphics,
+                None => continue
+            };
+            let supported = self.supported_features(&physical_device);
+            if required.iter().any(|feature| !supported.contains(feature)) { continue; }
+            let compute = if criteria.async_compute { families.iter().position(|family| family.supports_compute() && !family.supports_graphics()) } else { None };
+            let transfer = if criteria.dedicated_transfer { families.iter().position(|family| family.supports_transfer() && !family.supports_graphics() && !family.supports_compute()) } else { None };
+            let mut score = match physical_device.device_type()
+            {
+                PhysicalDeviceType::Discrete => if criteria.prefer_discrete { 1000 } else { 300 },
+                PhysicalDeviceType::Integrated => if criteria.prefer_discrete { 300 } else { 1000 },
+                PhysicalDeviceType::Virtual => 200,
+                PhysicalDeviceType::Cpu => 100,
+                PhysicalDeviceType::Other => 0
+            };
+            score += 10 * criteria.features.optional.iter().filter(|feature| supported.contains(feature)).count() as u32;
+            if compute.is_some() { score += 5; }
+            if transfer.is_some() { score += 5; }
+            if best.as_ref().is_none_or(|(best_score, ..)| score > *best_score) { best = Some((score, physical_device, graphics, compute, transfer)); }
+        }
+        let (_, physical_device, graphics, compute, transfer) = best.ok_or(Error::NoSuitableDevice)?;
+        let families = physical_device.queue_families();
+        let queues: Vec<(&QueueFamilyInfo, [f32; 1])> = [Some(graphics), compute, transfer].iter().flatten().map(|index| (&families[*index], [1.0])).collect();
+        let device = self.logical_device(&physical_device, &queues, &criteria.features)?;
+        let graphics = device.get_queue_family(&families[graphics]).get_queue(0);
+        let compute = compute.map(|index| device.get_queue_family(&families[index]).get_queue(0));
+        let transfer = transfer.map(|index| device.get_queue_family(&families[index]).get_queue(0));
+        Ok(AutoDevice { device, graphics, compute, transfer })
     }
 }
 
@@ -255,6 +584,139 @@ impl PhysicalDevice
     }
     
     pub fn queue_families(&self) -> &[QueueFamilyInfo] { &self.queue_family_properties }
+
+    pub fn device_type(&self) -> PhysicalDeviceType
+    {
+        match self.physical_device_properties.device_type
+        {
+            vk::PhysicalDeviceType::DISCRETE_GPU => PhysicalDeviceType::Discrete,
+            vk::PhysicalDeviceType::INTEGRATED_GPU => PhysicalDeviceType::Integrated,
+            vk::PhysicalDeviceType::VIRTUAL_GPU => PhysicalDeviceType::Virtual,
+            vk::PhysicalDeviceType::CPU => PhysicalDeviceType::Cpu,
+            _ => PhysicalDeviceType::Other
+        }
+    }
+
+    pub fn vendor_id(&self) -> u32 { self.physical_device_properties.vendor_id }
+    pub fn device_id(&self) -> u32 { self.physical_device_properties.device_id }
+    //encoding is vendor specific
+    pub fn driver_version(&self) -> u32 { self.physical_device_properties.driver_version }
+    pub fn api_version(&self) -> ApiVersion { ApiVersion::from_vk(self.physical_device_properties.api_version) }
+    //None if the instance API version is below 1.1 or the device supports neither 1.2 nor VK_KHR_driver_properties
+    pub fn driver(&self) -> Option<&DriverInfo> { self.driver.as_ref() }
+
+    pub fn limits(&self) -> Limits
+    {
+        let limits = &self.physical_device_properties.limits;
+        Limits
+        {
+            max_image_dimension_2d: limits.max_image_dimension2_d,
+            max_image_dimension_cube: limits.max_image_dimension_cube,
+            max_image_array_layers: limits.max_image_array_layers,
+            max_framebuffer_width: limits.max_framebuffer_width,
+            max_framebuffer_height: limits.max_framebuffer_height,
+            max_color_attachments: limits.max_color_attachments,
+            max_push_constants_size: limits.max_push_constants_size,
+            max_uniform_buffer_range: limits.max_uniform_buffer_range,
+            max_storage_buffer_range: limits.max_storage_buffer_range,
+            min_uniform_buffer_offset_alignment: limits.min_uniform_buffer_offset_alignment,
+            min_storage_buffer_offset_alignment: limits.min_storage_buffer_offset_alignment,
+            max_bound_descriptor_sets: limits.max_bound_descriptor_sets,
+            max_per_stage_descriptor_uniform_buffers: limits.max_per_stage_descriptor_uniform_buffers,
+            max_per_stage_descriptor_storage_buffers: limits.max_per_stage_descriptor_storage_buffers,
+            max_per_stage_descriptor_samplers: limits.max_per_stage_descriptor_samplers,
+            max_per_stage_descriptor_input_attachments: limits.max_per_stage_descriptor_input_attachments,
+            max_descriptor_set_uniform_buffers: limits.max_descriptor_set_uniform_buffers,
+            max_descriptor_set_storage_buffers: limits.max_descriptor_set_storage_buffers,
+            max_descriptor_set_samplers: limits.max_descriptor_set_samplers,
+            max_descriptor_set_input_attachments: limits.max_descriptor_set_input_attachments,
+            max_vertex_input_attributes: limits.max_vertex_input_attributes,
+            max_vertex_input_bindings: limits.max_vertex_input_bindings,
+            max_sampler_anisotropy: limits.max_sampler_anisotropy,
+            max_compute_shared_memory_size: limits.max_compute_shared_memory_size,
+            max_compute_work_group_count: limits.max_compute_work_group_count,
+            max_compute_work_group_invocations: limits.max_compute_work_group_invocations,
+            max_compute_work_group_size: limits.max_compute_work_group_size,
+            timestamp_period: limits.timestamp_period
+        }
+    }
+
+    pub fn memory_heaps(&self) -> Vec<MemoryHeap>
+    {
+        self.memory_properties.memory_heaps_as_slice().iter().map(|heap| MemoryHeap
+        {
+            size_in_bytes: heap.size,
+            device_local: heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL)
+        }).collect()
+    }
+
+    pub fn memory_types(&self) -> Vec<MemoryType>
+    {
+        self.memory_properties.memory_types_as_slice().iter().map(|ty| MemoryType
+        {
+            heap_index: ty.heap_index,
+            device_local: ty.property_flags.contains(vk::MemoryPropertyFlags::DEVICE_LOCAL),
+            host_visible: ty.property_flags.contains(vk::MemoryPropertyFlags::HOST_VISIBLE),
+            host_coherent: ty.property_flags.contains(vk::MemoryPropertyFlags::HOST_COHERENT),
+            host_cached: ty.property_flags.contains(vk::MemoryPropertyFlags::HOST_CACHED)
+        }).collect()
+    }
+
+    pub fn format_features(&self, channel: ImageChannelType) -> FormatFeatures
+    {
+        let properties = &self.format_properties[channel.index()];
+        let features = properties.optimal_tiling_features;
+        FormatFeatures
+        {
+            sampled: features.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE),
+            sampled_linear_filter: features.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR),
+            storage: features.contains(vk::FormatFeatureFlags::STORAGE_IMAGE),
+            color_attachment: features.contains(vk::FormatFeatureFlags::COLOR_ATTACHMENT),
+            color_attachment_blend: features.contains(vk::FormatFeatureFlags::COLOR_ATTACHMENT_BLEND),
+            depth_attachment: features.contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT),
+            blit_src: features.contains(vk::FormatFeatureFlags::BLIT_SRC),
+            blit_dst: features.contains(vk::FormatFeatureFlags::BLIT_DST),
+            transfer_src: features.contains(vk::FormatFeatureFlags::TRANSFER_SRC),
+            transfer_dst: features.contains(vk::FormatFeatureFlags::TRANSFER_DST)
+        }
+    }
+}
+
+impl DriverId
+{
+    fn from_vk(id: vk::DriverId) -> Self
+    {
+        match id
+        {
+            vk::DriverId::AMD_PROPRIETARY => Self::AmdProprietary,
+            vk::DriverId::AMD_OPEN_SOURCE => Self::AmdOpenSource,
+            vk::DriverId::MESA_RADV => Self::MesaRadv,
+            vk::DriverId::NVIDIA_PROPRIETARY => Self::NvidiaProprietary,
+            vk::DriverId::INTEL_PROPRIETARY_WINDOWS => Self::IntelProprietaryWindows,
+            vk::DriverId::INTEL_OPEN_SOURCE_MESA => Self::IntelOpenSourceMesa,
+            vk::DriverId::IMAGINATION_PROPRIETARY => Self::ImaginationProprietary,
+            vk::DriverId::QUALCOMM_PROPRIETARY => Self::QualcommProprietary,
+            vk::DriverId::ARM_PROPRIETARY => Self::ArmProprietary,
+            vk::DriverId::GOOGLE_SWIFTSHADER => Self::GoogleSwiftshader,
+            vk::DriverId::GGP_PROPRIETARY => Self::GgpProprietary,
+            vk::DriverId::BROADCOM_PROPRIETARY => Self::BroadcomProprietary,
+            vk::DriverId::MESA_LLVMPIPE => Self::MesaLlvmpipe,
+            vk::DriverId::MOLTENVK => Self::Moltenvk,
+            vk::DriverId::COREAVI_PROPRIETARY => Self::CoreaviProprietary,
+            vk::DriverId::JUICE_PROPRIETARY => Self::JuiceProprietary,
+            vk::DriverId::VERISILICON_PROPRIETARY => Self::VerisiliconProprietary,
+            vk::DriverId::MESA_TURNIP => Self::MesaTurnip,
+            vk::DriverId::MESA_V3DV => Self::MesaV3dv,
+            vk::DriverId::MESA_PANVK => Self::MesaPanvk,
+            vk::DriverId::SAMSUNG_PROPRIETARY => Self::SamsungProprietary,
+            vk::DriverId::MESA_VENUS => Self::MesaVenus,
+            vk::DriverId::MESA_DOZEN => Self::MesaDozen,
+            vk::DriverId::MESA_NVK => Self::MesaNvk,
+            vk::DriverId::IMAGINATION_OPEN_SOURCE_MESA => Self::ImaginationOpenSourceMesa,
+            vk::DriverId::MESA_AGXV => Self::MesaAgxv,
+            other => Self::Other(other.as_raw())
+        }
+    }
 }
 
 impl QueueFamilyInfo
@@ -264,7 +726,7 @@ impl QueueFamilyInfo
     pub fn supports_compute(&self) -> bool { self.queue_family_properties.queue_flags.contains(vk::QueueFlags::COMPUTE) }
     pub fn supports_transfer(&self) -> bool { self.queue_family_properties.queue_flags.contains(vk::QueueFlags::TRANSFER) }
     //pub fn supports_sparse_binding(&self) -> bool { self.queue_family_properties.queue_flags.contains(vk::QueueFlags::SPARSE_BINDING) }
-    pub fn supports_surface(&self) -> bool { self.surface_support }
+    pub fn supports_surface(&self, surface: &Surface) -> Result<bool, Error> { surface.supports(self.physical_device, self.index) }
 }
 
 impl PartialEq for QueueFamilyInfo
@@ -274,3 +736,18 @@ impl PartialEq for QueueFamilyInfo
         self.index == other.index
     }
 }
+
+impl Default for DeviceCriteria<'_>
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            features: DeviceFeatures::default(),
+            surface: None,
+            prefer_discrete: true,
+            async_compute: true,
+            dedicated_transfer: true
+        }
+    }
+}
@@ -3,9 +3,13 @@ use serde::{Serialize, Deserialize};
 
 const DEBUG_MODE: bool = cfg!(debug_assertions);
 
+//the derives refer to ::gru_vulkan, this also has to resolve inside this crate
+extern crate self as gru_vulkan;
+
 mod drop;
 mod instance;
 mod swapchain;
+mod offscreen;
 mod device;
 mod render_pass;
 mod pipeline;
@@ -14,8 +18,11 @@ mod image;
 mod descriptor;
 mod command;
 mod debug;
+mod error;
+mod features;
 pub use instance::*;
 pub use swapchain::*;
+//pub use offscreen::*;
 //pub use device::*;
 pub use render_pass::*;
 pub use pipeline::*;
@@ -23,44 +30,315 @@ pub use buffer::*;
 pub use image::*;
 //pub use descriptor::*;
 pub use command::*;
+pub use features::*;
 //pub use debug::*;
 
 use std::{marker::PhantomData, sync::{Arc, Mutex}, rc::Rc};
 use ash::{self, vk};
 use gpu_allocator::vulkan as alloc;
 
-pub use gru_vulkan_derive::{VertexAttributeGroupReprCpacked, InstanceAttributeGroupReprCpacked, StorageStructReprC, DescriptorStructReprC};
+pub use gru_vulkan_derive::{VertexAttributeGroupReprCpacked, InstanceAttributeGroupReprCpacked, StorageStructReprC, DescriptorStructReprC, Std140, Std430};
 pub use inline_spirv::include_spirv;
 
-//     #####     INSTANCE     #####
+//     #####     ERROR     #####
 
-struct Surface
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error
 {
-    loader: ash::khr::surface::Instance,
-    surface: vk::SurfaceKHR
+    OutOfHostMemory,
+    OutOfDeviceMemory,
+    DeviceLost,
+    SurfaceLost,
+    UnsupportedFormat,
+    UnsupportedFeature,
+    MissingFeatures(Box<[Feature]>),
+    NoSuitableDevice,
+    Misuse(String),
+    Loading(String),
+    Allocation(String),
+    Vulkan(vk::Result)
 }
 
-pub struct Instance
+//     #####     INSTANCE     #####
+
+struct RawInstance
 {
     #[allow(unused)] entry: ash::Entry,
     debug: Option<(ash::ext::debug_utils::Instance, vk::DebugUtilsMessengerEXT)>,
+    //the messenger refers to this as user data, it must not move
+    debug_state: Box<DebugState>,
+    debug_printf: bool,
     instance: ash::Instance,
-    surface: Option<Surface>
+    extensions: Box<[std::ffi::CString]>,
+    api_version: ApiVersion,
+    properties2: bool,
+    missing_layers: Box<[String]>,
+    missing_extensions: Box<[String]>
+}
+
+pub struct Instance(Arc<RawInstance>);
+
+struct RawSurface
+{
+    //must outlive the surface
+    #[allow(unused)] instance: Arc<RawInstance>,
+    loader: ash::khr::surface::Instance,
+    surface: vk::SurfaceKHR,
+    //headless surfaces have no extent of their own
+    extent: Option<(u32, u32)>
+}
+
+#[derive(Clone)]
+pub struct Surface(Arc<RawSurface>);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ApiVersion
+{
+    V1_0,
+    V1_1,
+    V1_2,
+    V1_3
+}
+
+pub struct InstanceBuilder<'a>
+{
+    app_name: std::ffi::CString,
+    app_version: u32,
+    api_version: ApiVersion,
+    layers: Vec<std::ffi::CString>,
+    extensions: Vec<std::ffi::CString>,
+    validation: bool,
+    headless: bool,
+    validation_features: ValidationFeatures,
+    debug_severity: DebugSeverity,
+    debug_message_types: Vec<DebugMessageType>,
+    debug_output: DebugOutput,
+    debug_printf_output: DebugPrintfOutput,
+    window: Option<&'a dyn HasBothHandles>,
+    //first name containing a nul byte, reported by build
+    invalid_name: Option<String>
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct ValidationFeatures
+{
+    pub synchronization: bool,
+    pub best_practices: bool,
+    //cannot be combined with debug_printf
+    pub gpu_assisted: bool,
+    //GL_EXT_debug_printf, see DebugPrintfOutput
+    pub debug_printf: bool
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum DebugSeverity
+{
+    Verbose,
+    Info,
+    Warning,
+    Error
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DebugMessageType
+{
+    General,
+    Validation,
+    Performance
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DebugMessage
+{
+    pub severity: DebugSeverity,
+    pub ty: DebugMessageType,
+    pub id_name: String,
+    pub message: String,
+    //names given with debug_named
+    pub objects: Vec<String>
+}
+
+pub enum DebugOutput
+{
+    //println!, or the log crate if the "log" feature is enabled
+    Print,
+    Callback(Box<dyn Fn(&DebugMessage) + Send + Sync>),
+    //collects the messages for Instance::take_debug_messages
+    //with panic_on_error, the next submit, present or take_debug_messages panics after an error message
+    Capture { panic_on_error: bool }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DebugPrintf
+{
+    //e.g. "Compute" or "Fragment"
+    pub stage: Option<String>,
+    //e.g. "Global invocation ID (x, y, z) = (0, 0, 0 )"
+    pub invocation: Option<String>,
+    pub message: String
+}
+
+pub enum DebugPrintfOutput
+{
+    //println!, or the log target "gru_vulkan::printf" if the "log" feature is enabled
+    Print,
+    Callback(Box<dyn Fn(&DebugPrintf) + Send + Sync>)
+}
+
+struct DebugState
+{
+    severity: DebugSeverity,
+    output: DebugOutput,
+    printf_output: DebugPrintfOutput,
+    captured: Mutex<Vec<DebugMessage>>,
+    //first error captured with panic_on_error, raised on the Rust side by raise_debug_error
+    error: Mutex<Option<String>>
 }
 
 pub struct PhysicalDevice
 {
     physical_device: vk::PhysicalDevice,
     physical_device_properties: vk::PhysicalDeviceProperties,
+    memory_properties: vk::PhysicalDeviceMemoryProperties,
+    format_properties: [vk::FormatProperties; ImageChannelType::ALL.len()],
+    driver: Option<DriverInfo>,
     queue_family_properties: Box<[QueueFamilyInfo]>
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PhysicalDeviceType
+{
+    Discrete,
+    Integrated,
+    Virtual,
+    Cpu,
+    Other
+}
+
+//VkDriverId, Other holds the raw value of drivers unknown to this version
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DriverId
+{
+    AmdProprietary,
+    AmdOpenSource,
+    MesaRadv,
+    NvidiaProprietary,
+    IntelProprietaryWindows,
+    IntelOpenSourceMesa,
+    ImaginationProprietary,
+    QualcommProprietary,
+    ArmProprietary,
+    GoogleSwiftshader,
+    GgpProprietary,
+    BroadcomProprietary,
+    MesaLlvmpipe,
+    Moltenvk,
+    CoreaviProprietary,
+    JuiceProprietary,
+    VerisiliconProprietary,
+    MesaTurnip,
+    MesaV3dv,
+    MesaPanvk,
+    SamsungProprietary,
+    MesaVenus,
+    MesaDozen,
+    MesaNvk,
+    ImaginationOpenSourceMesa,
+    MesaAgxv,
+    Other(i32)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DriverInfo
+{
+    pub id: DriverId,
+    pub name: String,
+    pub info: String
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Limits
+{
+    pub max_image_dimension_2d: u32,
+    pub max_image_dimension_cube: u32,
+    pub max_image_array_layers: u32,
+    pub max_framebuffer_width: u32,
+    pub max_framebuffer_height: u32,
+    pub max_color_attachments: u32,
+    pub max_push_constants_size: u32,
+    pub max_uniform_buffer_range: u32,
+    pub max_storage_buffer_range: u32,
+    pub min_uniform_buffer_offset_alignment: u64,
+    pub min_storage_buffer_offset_alignment: u64,
+    pub max_bound_descriptor_sets: u32,
+    pub max_per_stage_descriptor_uniform_buffers: u32,
+    pub max_per_stage_descriptor_storage_buffers: u32,
+    pub max_per_stage_descriptor_samplers: u32,
+    pub max_per_stage_descriptor_input_attachments: u32,
+    pub max_descriptor_set_uniform_buffers: u32,
+    pub max_descriptor_set_storage_buffers: u32,
+    pub max_descriptor_set_samplers: u32,
+    pub max_descriptor_set_input_attachments: u32,
+    pub max_vertex_input_attributes: u32,
+    pub max_vertex_input_bindings: u32,
+    pub max_sampler_anisotropy: f32,
+    pub max_compute_shared_memory_size: u32,
+    pub max_compute_work_group_count: [u32; 3],
+    pub max_compute_work_group_invocations: u32,
+    pub max_compute_work_group_size: [u32; 3],
+    pub timestamp_period: f32
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MemoryHeap
+{
+    pub size_in_bytes: u64,
+    pub device_local: bool
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MemoryType
+{
+    pub heap_index: u32,
+    pub device_local: bool,
+    pub host_visible: bool,
+    pub host_coherent: bool,
+    pub host_cached: bool
+}
+
+//support of an ImageChannelType with optimal tiling (as used by Image)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FormatFeatures
+{
+    pub sampled: bool,
+    pub sampled_linear_filter: bool,
+    pub storage: bool,
+    pub color_attachment: bool,
+    pub color_attachment_blend: bool,
+    pub depth_attachment: bool,
+    pub blit_src: bool,
+    pub blit_dst: bool,
+    pub transfer_src: bool,
+    pub transfer_dst: bool
+}
+
+#[derive(Clone)]
+pub struct DeviceCriteria<'a>
+{
+    pub features: DeviceFeatures,
+    //the graphics queue must be able to present to this surface
+    pub surface: Option<&'a Surface>,
+    pub prefer_discrete: bool,
+    pub async_compute: bool,
+    pub dedicated_transfer: bool
+}
+
 #[derive(Clone)]
 pub struct QueueFamilyInfo
 {
     index: usize,
-    queue_family_properties: vk::QueueFamilyProperties,
-    surface_support: bool
+    physical_device: vk::PhysicalDevice,
+    queue_family_properties: vk::QueueFamilyProperties
 }
 
 //     #####     DEVICE     #####
@@ -76,7 +354,7 @@ pub struct QueueFamily
     index: usize,
     queues: Box<[Arc<Mutex<Queue>>]>,
     flags: vk::QueueFlags,
-    surface_support: bool
+    physical_device: vk::PhysicalDevice
 }
 
 struct Props
@@ -91,6 +369,8 @@ struct RawDevice
     debug_utils: Option<ash::ext::debug_utils::Device>,
     physical_device: vk::PhysicalDevice,
     props: Props,
+    features: Box<[Feature]>,
+    swapchain_support: bool,
     logical_device: ash::Device,
     allocator: Option<Mutex<alloc::Allocator>>,
     queue_families: Box<[QueueFamily]>,
@@ -100,9 +380,25 @@ struct RawDevice
 #[derive(Clone)]
 pub struct Device(Arc<RawDevice>);
 
+pub struct AutoDevice
+{
+    pub device: Device,
+    //supports presenting to the surface of the criteria
+    pub graphics: Arc<Mutex<Queue>>,
+    //a compute queue of a family without graphics support
+    pub compute: Option<Arc<Mutex<Queue>>>,
+    //a transfer queue of a family without graphics or compute support
+    pub transfer: Option<Arc<Mutex<Queue>>>
+}
+
 pub struct Swapchain
 {
     device: Arc<RawDevice>,
+    //must outlive the swapchain
+    #[allow(unused)] surface: Arc<RawSurface>,
+    format: SwapchainFormat,
+    present_mode: PresentMode,
+    usage: SwapchainUsage,
     width: u32,
     height: u32,
     swapchain_loader: ash::khr::swapchain::Device,
@@ -110,7 +406,137 @@ pub struct Swapchain
     swapchain_images: Box<[vk::Image]>,
     swapchain_image_views: Box<[vk::ImageView]>,
     count: usize,
-    cycle_index: std::cell::Cell<usize>
+    cycle_index: std::cell::Cell<usize>,
+    //the swapchain this one replaced (which holds the one before), their images may still be presented
+    retired: Option<Box<Swapchain>>
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SwapchainFormat
+{
+    //B8G8R8A8_SRGB, sRGB nonlinear
+    Srgb,
+    //B8G8R8A8_UNORM, sRGB nonlinear, for manual gamma correction
+    Unorm,
+    //A2B10G10R10_UNORM_PACK32, HDR10 ST2084
+    Hdr10,
+    //R16G16B16A16_SFLOAT, extended sRGB linear
+    ExtendedSrgb
+}
+
+//the acquire/present cycle shared by Swapchain and OffscreenSwapchain
+pub trait PresentTarget
+{
+    fn acquire_next_image(&self, signal: Option<&Semaphore>, mark: Option<&Fence>, timeout: Option<std::time::Duration>) -> Result<AcquireOutcome, Error>;
+    fn present<const N: usize>(&self, index: SwapchainObjectIndex, queue: &Queue, wait: [&Semaphore; N]) -> Result<PresentOutcome, Error>;
+    fn get_image<'a>(&'a self, index: &'a SwapchainObjectIndex) -> SwapchainImage<'a>;
+    fn dimensions(&self) -> (u32, u32);
+    fn format(&self) -> SwapchainFormat;
+    fn usage(&self) -> SwapchainUsage;
+    fn image_count(&self) -> usize;
+    fn color_attachment(&self, load: SwapchainLoad) -> RenderPassColorAttachment;
+    fn new_objects<T>(&self, constructor: &mut dyn FnMut(&SwapchainObjectIndex) -> T) -> SwapchainObjects<T>;
+    //destroys replaced targets, FrameLoop calls this once their frames have finished
+    fn drop_retired(&mut self) {}
+}
+
+//a swapchain backed by ordinary images, for rendering without a window
+pub struct OffscreenSwapchain
+{
+    device: Arc<RawDevice>,
+    queue: Arc<Mutex<Queue>>,
+    command_buffer: std::cell::RefCell<CommandBuffer>,
+    //signaled once the readback of the pending frame has finished
+    fence: Fence,
+    //the presented frame whose readback may still be running
+    pending: std::cell::Cell<Option<usize>>,
+    images: Box<[Image]>,
+    readback: ImageBuffer,
+    output: std::cell::RefCell<OffscreenOutput>,
+    format: SwapchainFormat,
+    usage: SwapchainUsage,
+    width: u32,
+    height: u32,
+    next_index: std::cell::Cell<usize>
+}
+
+#[derive(Clone, Copy)]
+pub struct OffscreenSwapchainInfo
+{
+    pub width: u32,
+    pub height: u32,
+    pub image_count: u32,
+    pub format: SwapchainFormat,
+    //transfer_src is always enabled for the readback
+    pub usage: SwapchainUsage
+}
+
+pub enum OffscreenOutput
+{
+    Discard,
+    //see OffscreenSwapchain::readback
+    Readback,
+    //called with every presented frame once its readback has finished
+    Callback(Box<dyn FnMut(&SwapchainObjectIndex, &ImageBuffer)>)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PresentMode
+{
+    Immediate,
+    Mailbox,
+    //always supported
+    Fifo,
+    FifoRelaxed
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompositeAlpha
+{
+    Opaque,
+    PreMultiplied,
+    PostMultiplied,
+    Inherit
+}
+
+//the images can always be used as color attachments
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct SwapchainUsage
+{
+    pub transfer_src: bool,
+    pub transfer_dst: bool,
+    //for compute-written output
+    pub storage: bool
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SurfaceCapabilities
+{
+    pub min_image_count: u32,
+    //None if unlimited
+    pub max_image_count: Option<u32>,
+    //None if the swapchain decides
+    pub current_extent: Option<(u32, u32)>,
+    pub min_extent: (u32, u32),
+    pub max_extent: (u32, u32),
+    pub formats: Vec<SwapchainFormat>,
+    pub present_modes: Vec<PresentMode>,
+    pub usage: SwapchainUsage,
+    pub composite_alpha: Vec<CompositeAlpha>
+}
+
+#[derive(Clone, Copy)]
+pub struct SwapchainInfo<'a>
+{
+    pub surface: &'a Surface,
+    //the first supported format is taken
+    pub formats: &'a [SwapchainFormat],
+    //the first supported mode is taken, falls back to Fifo
+    pub present_modes: &'a [PresentMode],
+    //clamped to the surface capabilities
+    pub image_count: u32,
+    pub usage: SwapchainUsage,
+    pub composite_alpha: CompositeAlpha
 }
 
 #[derive(Clone, Copy)]
@@ -118,6 +544,8 @@ pub struct SwapchainImage<'a>
 {
     image: &'a vk::Image,
     image_view: &'a vk::ImageView,
+    format: SwapchainFormat,
+    usage: SwapchainUsage,
     width: u32,
     height: u32
 }
@@ -144,20 +572,39 @@ pub enum AttributeType
 {
     F1, F2, F3, F4,
     I1, I2, I3, I4,
-    U1, U2, U3, U4
+    U1, U2, U3, U4,
+    Unorm8x4, Snorm8x4,
+    Unorm16x2, Unorm16x4, Snorm16x2, Snorm16x4,
+    U16x2, U16x4, I16x2, I16x4,
+    F16x2, F16x4,
+    A2Bgr10Unorm, A2Bgr10Snorm,
+    M2, M3, M3x4, M4
+}
+
+//implemented by all types usable as attributes
+pub trait AttributeTypeOf
+{
+    const TYPE: AttributeType;
+    //used by the attribute group derives for the shader inputs
+    const GLSL_TYPE: &'static str = Self::TYPE.glsl_type();
 }
 
 pub trait AttributeGroupReprCpacked
 {
     const RATE: InputRate;
-    const ATTRIBUTES: &'static [(AttributeLocation, AttributeType)];
+    //location, type and byte offset of every shader input
+    const ATTRIBUTES: &'static [(AttributeLocation, AttributeType, u32)];
+    const STRIDE: u32;
+    //the matching "layout(location = ?) in" declarations, generated by the derive
+    const GLSL: &'static str = "";
 }
 
 #[derive(Clone, Copy)]
 pub struct AttributeGroupInfo
 {
     rate: InputRate,
-    attributes: &'static [(AttributeLocation, AttributeType)],
+    attributes: &'static [(AttributeLocation, AttributeType, u32)],
+    stride: u32
 }
 
 pub struct BufferType
@@ -169,7 +616,8 @@ pub struct BufferType
     indices: bool,
     attributes: bool,
     uniforms: bool,
-    storage: bool
+    storage: bool,
+    indirect: bool
 }
 
 pub struct BufferTypeBuilder(BufferType);
@@ -190,7 +638,51 @@ pub struct Buffer
     buffer: vk::Buffer,
     buffer_usage: BufferUsage,
     layout_id: u32,
-    size_in_bytes: u64
+    size_in_bytes: u64,
+    //the buffers of a GpuVec share the counter, the buffer is stale if its generation is older,
+    //descriptor sets keep a Weak of it to catch dropped and stale buffers in debug
+    generation: u32,
+    current_generation: Arc<std::sync::atomic::AtomicU32>
+}
+
+//a Dynamic buffer with a single view, reallocated when it runs out of capacity
+pub struct GpuVec<T>
+{
+    device: Device,
+    add: fn(&mut BufferTypeBuilder, u32) -> BufferView<T>,
+    buffer: Buffer,
+    view: BufferView<T>,
+    len: u32,
+    //the frame the GpuVec is used in, see GpuVec::begin_frame
+    frame: usize,
+    //buffers replaced while the frame in flight could still read them
+    retired: std::collections::VecDeque<(usize, Buffer)>
+}
+
+//persistently mapped upload memory, sub-allocated in a ring
+pub struct StagingRing
+{
+    device: Arc<RawDevice>,
+    allocation: Option<alloc::Allocation>,
+    buffer: vk::Buffer,
+    size_in_bytes: u64,
+    //ever growing byte positions, the offset in the buffer is position % size_in_bytes
+    head: u64,
+    tail: u64,
+    //the frame the uploads are recorded for, see StagingRing::begin_frame
+    frame: usize,
+    //the end position of every earlier frame that is still in flight, oldest first
+    in_flight: std::collections::VecDeque<(usize, u64)>
+}
+
+//where StagingRing::upload writes to and who reads the data afterwards
+#[derive(Clone, Copy)]
+pub struct UploadInfo
+{
+    //the first element of the view that is written
+    pub offset: usize,
+    pub usage: CopyViewUsage,
+    pub stage: WaitStage
 }
 
 //     #####     IMAGE     #####
@@ -213,6 +705,7 @@ pub enum ImageChannelType
     BgraUint,
     RgbaSfloat,
     RgbaShalffloat,
+    A2Bgr10Unorm,
     RSrgb,
     RSnorm,
     RUnorm,
@@ -288,15 +781,49 @@ pub struct Sampler
 pub trait StorageStructReprC { }
 pub trait DescriptorStructReprC: Copy { }
 
+//GLSL layout of uniform blocks, the derive also implements DescriptorStructReprC
+//and fails to compile if a field is not at its GLSL offset
+pub unsafe trait Std140
+{
+    const ALIGN: usize;
+    const SIZE: usize;
+    //the type of members of this type in GLSL declarations, empty for padding which is left out
+    const GLSL_TYPE: &'static str;
+    //the matching uniform block preceded by the declarations of its nested structs, generated by the derive
+    const GLSL: &'static str = "";
+    //the guarded struct declaration for nesting this type in other blocks, generated by the derive
+    const GLSL_STRUCT: &'static str = "";
+}
+
+//GLSL layout of storage blocks, the derive also implements StorageStructReprC
+//and fails to compile if a field is not at its GLSL offset
+pub unsafe trait Std430
+{
+    const ALIGN: usize;
+    const SIZE: usize;
+    //the type of members of this type in GLSL declarations, empty for padding which is left out
+    const GLSL_TYPE: &'static str;
+    //the matching buffer block preceded by the declarations of its nested structs, generated by the derive
+    const GLSL: &'static str = "";
+    //the guarded struct declaration for nesting this type in other blocks, generated by the derive
+    const GLSL_STRUCT: &'static str = "";
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum DescriptorBindingType
 {
     Storage,
     Struct { size_in_bytes: u32 },
     Sampler { image_channel_type: ImageChannelType },
-    SubpassInput { image_channel_type: ImageChannelType }
+    SubpassInput { image_channel_type: ImageChannelType },
+    //a single element, the index is chosen at bind time with a DynamicOffset
+    DynamicStorage,
+    DynamicStruct { size_in_bytes: u32 }
 }
 
+//per set, above the Vulkan limits of common devices, lets the dynamic offsets be bound without allocating
+const MAX_DYNAMIC_BINDINGS: usize = 32;
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct DescriptorVisibility
 {
@@ -334,7 +861,18 @@ pub struct DescriptorSet
 {
     pool: Arc<DescriptorPool>,
     descriptor_set: vk::DescriptorSet,
-    layout: Arc<RawDescriptorSetLayout>
+    layout: Arc<RawDescriptorSetLayout>,
+    //binding, generation counter and generation of every buffer the set has been updated with
+    buffers: Vec<(u32, std::sync::Weak<std::sync::atomic::AtomicU32>, u32)>,
+    //binding and layout id of the view of every dynamic binding
+    dynamic_views: Vec<(u32, u32)>
+}
+
+#[derive(Clone, Copy)]
+pub struct DynamicOffset
+{
+    layout_id: u32,
+    offset_in_bytes: u32
 }
 
 //     #####     RENDER STUFF     #####
@@ -0,0 +1,184 @@
+use super::*;
+
+impl Device
+{
+    //the queue is used to signal acquired images and to read back presented ones
+    pub fn new_offscreen_swapchain(&self, queue_family: &QueueFamily, queue: Arc<Mutex<Queue>>, info: &OffscreenSwapchainInfo, output: OffscreenOutput) -> Result<OffscreenSwapchain, Error>
+    {
+        if info.image_count == 0 { return Err(Error::Misuse(String::from("Device::new_offscreen_swapchain: At least one image is required."))); }
+        let image_type = ImageType { channel: info.format.image_channel_type(), width: info.width, height: info.height, layers: ImageLayers::Single };
+        let image_usage = ImageUsage::Attachment { depth: false, samples: Msaa::None, texture: false, transfer_src: true };
+        let usage = SwapchainUsage { transfer_src: true, ..info.usage };
+        let images = (0..info.image_count)
+            .map(|_| self.new_image_with_flags(image_type, image_usage, usage.vk_image_usage()))
+            .collect::<Result<_, Error>>()?;
+        let command_buffer = self.new_command_pool(queue_family)?.new_command_buffer()?;
+        Ok(OffscreenSwapchain
+        {
+            device: self.0.clone(),
+            queue,
+            command_buffer: std::cell::RefCell::new(command_buffer),
+            fence: self.new_fence(false)?,
+            pending: std::cell::Cell::new(None),
+            images,
+            readback: self.new_image_buffer(image_type)?,
+            output: std::cell::RefCell::new(output),
+            format: info.format,
+            usage,
+            width: info.width,
+            height: info.height,
+            next_index: std::cell::Cell::new(0)
+        })
+    }
+}
+
+impl OffscreenSwapchain
+{
+    //the images are cycled, the readback of an image has always finished when it is acquired again
+    pub fn acquire_next_image(&self, signal: Option<&Semaphore>, mark: Option<&Fence>, _timeout: Option<std::time::Duration>) -> Result<AcquireOutcome, Error>
+    {
+        let index = self.next_index.get();
+        self.next_index.set((index + 1) % self.images.len());
+        if self.pending.get() == Some(index) { self.finish()?; }
+        if signal.is_some() || mark.is_some()
+        {
+            let semaphores = signal.map(|semaphore| semaphore.semaphore);
+            let submit_info = vk::SubmitInfo::default().signal_semaphores(semaphores.as_slice());
+            let fence = mark.map(|mark| mark.fence).unwrap_or(vk::Fence::null());
+            let queue = self.queue.lock().unwrap();
+            unsafe { self.device.logical_device.queue_submit(queue.queue, &[submit_info], fence) }?;
+        }
+        Ok(AcquireOutcome::Ok(SwapchainObjectIndex { index }))
+    }
+
+    //waits for the readback of the pending frame and delivers it to the output, does nothing without one
+    pub fn finish(&self) -> Result<(), Error>
+    {
+        if let Some(index) = self.pending.take()
+        {
+            let device = &self.device.logical_device;
+            unsafe { device.wait_for_fences(std::slice::from_ref(&self.fence.fence), true, u64::MAX) }?;
+            unsafe { device.reset_fences(std::slice::from_ref(&self.fence.fence)) }?;
+            if let OffscreenOutput::Callback(callback) = &mut *self.output.borrow_mut() { callback(&SwapchainObjectIndex { index }, &self.readback); }
+        }
+        Ok(())
+    }
+
+    //submits the readback without waiting, the returned fence is signaled once it has finished,
+    //the frame is delivered to the output by the next present, acquire of the same image or finish,
+    //as the next present waits for this readback only one frame is in flight at a time
+    pub fn present<const N: usize>(&self, index: SwapchainObjectIndex, queue: &Queue, wait: [&Semaphore; N]) -> Result<&Fence, Error>
+    {
+        //the command buffer, fence and readback buffer are shared by all frames
+        self.finish()?;
+        let output = self.output.borrow();
+        let command_buffer = self.command_buffer.borrow_mut();
+        let device = &self.device.logical_device;
+        let image = self.images[index.index].image;
+        let layout = self.device.present_layout();
+        let readback = !matches!(*output, OffscreenOutput::Discard);
+        unsafe
+        {
+            let command_buffer_begin_info = vk::CommandBufferBeginInfo::default()
+                .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+            device.begin_command_buffer(command_buffer.command_buffer, &command_buffer_begin_info)?;
+            if readback
+            {
+                let subresource_range = vk::ImageSubresourceRange
+                {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    base_mip_level: 0,
+                    level_count: 1,
+                    base_array_layer: 0,
+                    layer_count: 1
+                };
+                let mut barrier = vk::ImageMemoryBarrier::default()
+                    .image(image)
+                    .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+                    .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
+                    .old_layout(layout)
+                    .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                    .subresource_range(subresource_range)
+                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED);
+                let region = vk::BufferImageCopy
+                {
+                    image_subresource: vk::ImageSubresourceLayers { aspect_mask: vk::ImageAspectFlags::COLOR, mip_level: 0, base_array_layer: 0, layer_count: 1 },
+                    image_extent: vk::Extent3D { width: self.width, height: self.height, depth: 1 },
+                    ..Default::default()
+                };
+                device.cmd_pipeline_barrier(command_buffer.command_buffer, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
+                device.cmd_copy_image_to_buffer(command_buffer.command_buffer, image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, self.readback.buffer, &[region]);
+                barrier = barrier
+                    .src_access_mask(vk::AccessFlags::TRANSFER_READ)
+                    .dst_access_mask(vk::AccessFlags::MEMORY_READ)
+                    .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                    .new_layout(layout);
+                let buffer_barrier = vk::BufferMemoryBarrier::default()
+                    .buffer(self.readback.buffer)
+                    .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                    .dst_access_mask(vk::AccessFlags::HOST_READ)
+                    .size(vk::WHOLE_SIZE)
+                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED);
+                device.cmd_pipeline_barrier(command_buffer.command_buffer, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::HOST | vk::PipelineStageFlags::BOTTOM_OF_PIPE, vk::DependencyFlags::empty(), &[], &[buffer_barrier], &[barrier]);
+            }
+            device.end_command_buffer(command_buffer.command_buffer)?;
+        }
+        let wait_semaphores = wait.map(|wait| wait.semaphore);
+        let wait_dst_stage_mask = [vk::PipelineStageFlags::ALL_COMMANDS; N];
+        let submit_info = vk::SubmitInfo::default()
+            .command_buffers(std::slice::from_ref(&command_buffer.command_buffer))
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_dst_stage_mask);
+        unsafe { device.queue_submit(queue.queue, &[submit_info], self.fence.fence) }?;
+        self.pending.set(Some(index.index));
+        Ok(&self.fence)
+    }
+
+    pub fn get_image<'a>(&'a self, index: &'a SwapchainObjectIndex) -> SwapchainImage<'a>
+    {
+        let image = &self.images[index.index];
+        SwapchainImage
+        {
+            image: &image.image,
+            image_view: &image.image_view,
+            format: self.format,
+            usage: self.usage,
+            width: self.width,
+            height: self.height
+        }
+    }
+
+    //the last finished frame with OffscreenOutput::Readback, see finish
+    pub fn readback(&self) -> &ImageBuffer { &self.readback }
+
+    pub fn dimensions(&self) -> (u32, u32) { (self.width, self.height) }
+    pub fn format(&self) -> SwapchainFormat { self.format }
+    pub fn usage(&self) -> SwapchainUsage { self.usage }
+    pub fn image_count(&self) -> usize { self.images.len() }
+
+    //a render pass attachment matching the format of this swapchain
+    pub fn color_attachment(&self, load: SwapchainLoad) -> RenderPassColorAttachment
+    {
+        RenderPassColorAttachment::Swapchain { format: self.format, load }
+    }
+
+    pub fn new_objects<T>(&self, constructor: &mut dyn FnMut(&SwapchainObjectIndex) -> T) -> SwapchainObjects<T>
+    {
+        SwapchainObjects { objects: (0..self.images.len()).map(|index| constructor(&SwapchainObjectIndex { index })).collect() }
+    }
+}
+
+impl PresentTarget for OffscreenSwapchain
+{
+    fn acquire_next_image(&self, signal: Option<&Semaphore>, mark: Option<&Fence>, timeout: Option<std::time::Duration>) -> Result<AcquireOutcome, Error> { self.acquire_next_image(signal, mark, timeout) }
+    fn present<const N: usize>(&self, index: SwapchainObjectIndex, queue: &Queue, wait: [&Semaphore; N]) -> Result<PresentOutcome, Error> { self.present(index, queue, wait).map(|_| PresentOutcome::Ok) }
+    fn get_image<'a>(&'a self, index: &'a SwapchainObjectIndex) -> SwapchainImage<'a> { self.get_image(index) }
+    fn dimensions(&self) -> (u32, u32) { self.dimensions() }
+    fn format(&self) -> SwapchainFormat { self.format() }
+    fn usage(&self) -> SwapchainUsage { self.usage() }
+    fn image_count(&self) -> usize { self.image_count() }
+    fn color_attachment(&self, load: SwapchainLoad) -> RenderPassColorAttachment { self.color_attachment(load) }
+    fn new_objects<T>(&self, constructor: &mut dyn FnMut(&SwapchainObjectIndex) -> T) -> SwapchainObjects<T> { self.new_objects(constructor) }
+}
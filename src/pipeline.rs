@@ -37,7 +37,7 @@ macro_rules! frag_shader
 
 impl Device
 {
-	pub fn new_pipeline_layout(&self, descriptors: &[&DescriptorSetLayout], push_constant: Option<PushConstantInfo>) -> PipelineLayout
+	pub fn new_pipeline_layout(&self, descriptors: &[&DescriptorSetLayout], push_constant: Option<PushConstantInfo>) -> Result<PipelineLayout, Error>
     {
         let descriptor_set_layouts: Vec<_> = descriptors.iter().map(|info| info.0.descriptor_set_layout).collect();
         let mut push_constant_ranges = Vec::new();
@@ -45,8 +45,8 @@ impl Device
         {
             if DEBUG_MODE
             {
-                if push_constant.size % 4 != 0 { panic!("Device::new_pipeline_layout: Push constant size is not a multiple of 4."); }
-                if push_constant.size > 128 { panic!("Device::new_pipeline_layout: Push constant size is larger than 128 bytes."); }
+                if push_constant.size % 4 != 0 { return Err(Error::Misuse(String::from("Device::new_pipeline_layout: Push constant size is not a multiple of 4."))); }
+                if push_constant.size > 128 { return Err(Error::Misuse(String::from("Device::new_pipeline_layout: Push constant size is larger than 128 bytes."))); }
             }
             let shader_stages =
                 if push_constant.visibility.vertex { vk::ShaderStageFlags::VERTEX } else { vk::ShaderStageFlags::empty() }
@@ -58,8 +58,8 @@ impl Device
         let pipeline_layout_info = vk::PipelineLayoutCreateInfo::default()
             .set_layouts(&descriptor_set_layouts)
             .push_constant_ranges(&push_constant_ranges);
-        let pipeline_layout = unsafe { self.0.logical_device.create_pipeline_layout(&pipeline_layout_info, None) }.unwrap();
-        PipelineLayout { device: self.0.clone(), layout: pipeline_layout, push_constant }
+        let pipeline_layout = unsafe { self.0.logical_device.create_pipeline_layout(&pipeline_layout_info, None) }?;
+        Ok(PipelineLayout { device: self.0.clone(), layout: pipeline_layout, push_constant })
     }
     
     pub fn new_pipeline
@@ -72,13 +72,34 @@ impl Device
         attributes: &[AttributeGroupInfo],
         layout: &PipelineLayout,
         info: &PipelineInfo
-    ) -> Pipeline
+    ) -> Result<Pipeline, Error>
     {
+        if DEBUG_MODE
+        {
+            let require = |feature: Feature, usage: &str| if self.feature_enabled(feature) { Ok(()) } else { Err(Error::Misuse(format!("Device::new_pipeline: {usage} requires Feature::{feature:?}."))) };
+            match info.polygon
+            {
+                PipelinePolygon::Point => require(Feature::FillModeNonSolid, "PipelinePolygon::Point")?,
+                PipelinePolygon::Line => require(Feature::FillModeNonSolid, "PipelinePolygon::Line")?,
+                PipelinePolygon::Fill => {}
+            }
+            if info.depth_test.depth_clamp_enable() { require(Feature::DepthClamp, "DepthTest::Clamp")?; }
+            if info.line_width != 1.0 { require(Feature::WideLines, "A line_width other than 1.0")?; }
+            if info.min_sample_shading.is_some() { require(Feature::SampleRateShading, "min_sample_shading")?; }
+        }
         //shader
         let vertex_shader_create_info = vk::ShaderModuleCreateInfo::default().code(&vertex_shader_spirv);
-        let vertex_shader_module = unsafe { self.0.logical_device.create_shader_module(&vertex_shader_create_info, None) }.unwrap();
+        let vertex_shader_module = unsafe { self.0.logical_device.create_shader_module(&vertex_shader_create_info, None) }?;
         let fragment_shader_create_info = vk::ShaderModuleCreateInfo::default().code(&fragment_shader_spirv);
-        let fragment_shader_module = unsafe { self.0.logical_device.create_shader_module(&fragment_shader_create_info, None) }.unwrap();
+        let fragment_shader_module = match unsafe { self.0.logical_device.create_shader_module(&fragment_shader_create_info, None) }
+        {
+            Ok(fragment_shader_module) => fragment_shader_module,
+            Err(error) =>
+            {
+                unsafe { self.0.logical_device.destroy_shader_module(vertex_shader_module, None); }
+                return Err(error.into());
+            }
+        };
         let main_function_name = std::ffi::CString::new("main").unwrap();
         let vertex_shader_stage = vk::PipelineShaderStageCreateInfo::default()
             .stage(vk::ShaderStageFlags::VERTEX)
@@ -94,22 +115,26 @@ impl Device
         let mut vertex_attribute_descriptions = Vec::new();
         for (binding, group) in attributes.iter().enumerate()
         {
-            let mut offset = 0;
             for attribute in group.attributes
             {
-                vertex_attribute_descriptions.push(vk::VertexInputAttributeDescription
+                //matrices are split into columns at consecutive locations
+                let locations = attribute.1.locations();
+                let column_size_in_bytes = attribute.1.size_in_bytes() / locations;
+                for column in 0..locations
                 {
-                    binding: binding as u32,
-                    location: attribute.0.0,
-                    offset,
-                    format: attribute.1.vk_format()
-                });
-                offset += attribute.1.size_in_bytes();
+                    vertex_attribute_descriptions.push(vk::VertexInputAttributeDescription
+                    {
+                        binding: binding as u32,
+                        location: attribute.0.0 + column,
+                        offset: attribute.2 + column * column_size_in_bytes,
+                        format: attribute.1.vk_format()
+                    });
+                }
             }
             vertex_binding_descriptions.push(vk::VertexInputBindingDescription
             {
                 binding: binding as u32,
-                stride: offset,
+                stride: group.stride,
                 input_rate: match group.rate
                 {
                     InputRate::Vertex => vk::VertexInputRate::VERTEX,
@@ -172,13 +197,14 @@ impl Device
             .layout(layout.layout)
             .render_pass(render_pass.render_pass)
             .subpass(subpass);
-        let pipeline = unsafe { self.0.logical_device.create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_info], None) }.unwrap()[0];
+        let pipeline = unsafe { self.0.logical_device.create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_info], None) };
         unsafe
         {
             self.0.logical_device.destroy_shader_module(fragment_shader_module, None);
             self.0.logical_device.destroy_shader_module(vertex_shader_module, None);
         }
-        Pipeline { device: self.0.clone(), pipeline }
+        let pipeline = pipeline.map_err(|(_, result)| result)?[0];
+        Ok(Pipeline { device: self.0.clone(), pipeline })
     }
 }
 
@@ -3,7 +3,7 @@ use std::ops::Range;
 
 impl Device
 {
-	pub fn new_render_pass(&self, info: RenderPassInfo) -> RenderPass
+	pub fn new_render_pass(&self, info: RenderPassInfo) -> Result<RenderPass, Error>
     {
         let RenderPassInfo
         {
@@ -22,17 +22,17 @@ impl Device
         {
             let attachment = match attachment
             {
-                RenderPassColorAttachment::Swapchain(load) =>
+                RenderPassColorAttachment::Swapchain { format, load } =>
                 {  
                     clear_colors.push(load.vk_clear_value());
                     vk::AttachmentDescription::default()
-                        .format(Swapchain::IMAGE_CHANNEL_TYPE.vk_format())
+                        .format(format.image_channel_type().vk_format())
                         .load_op(load.vk_attachment_load_op())
                         .store_op(vk::AttachmentStoreOp::STORE)
                         .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
                         .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
                         .initial_layout(vk::ImageLayout::UNDEFINED)
-                        .final_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                        .final_layout(self.0.present_layout())
                         .samples(vk::SampleCountFlags::TYPE_1)
                 },
                 RenderPassColorAttachment::Image { image_channel_type, samples, load, store, initial_layout, final_layout } =>
@@ -53,7 +53,7 @@ impl Device
         }
         if let Some(depth_attachment) = depth_attachment
         {
-            if DEBUG_MODE && !depth_attachment.image_channel_type.has_depth() { panic!("Device::new_render_pass: This ImageChannelType hat no depth component."); }
+            if DEBUG_MODE && !depth_attachment.image_channel_type.has_depth() { return Err(Error::Misuse(String::from("Device::new_render_pass: This ImageChannelType hat no depth component."))); }
             clear_colors.push(depth_attachment.load.vk_clear_value());
             attachments.push(
             {
@@ -190,7 +190,7 @@ impl Device
         {
             match attachment
             {
-                RenderPassColorAttachment::Swapchain(_) =>
+                RenderPassColorAttachment::Swapchain { .. } =>
                 {
                     subpass_dependencies.push(vk::SubpassDependency::default()
                         .src_subpass(vk::SUBPASS_EXTERNAL)
@@ -266,25 +266,25 @@ impl Device
                 #[allow(unused_parens)] break 'rp (unsafe { self.0.logical_device.create_render_pass(&render_pass_info, None) });
             }
             #[allow(unused_parens)] break 'rp (unsafe { self.0.logical_device.create_render_pass(&render_pass_info, None) });
-        }.unwrap();
-        RenderPass { device: self.0.clone(), render_pass, clear_values: Box::from(clear_colors) }
+        }?;
+        Ok(RenderPass { device: self.0.clone(), render_pass, clear_values: Box::from(clear_colors) })
     }
 
-    pub fn new_framebuffer(&self, render_pass: &RenderPass, attachments: &[FramebufferAttachment]) -> Framebuffer
+    pub fn new_framebuffer(&self, render_pass: &RenderPass, attachments: &[FramebufferAttachment]) -> Result<Framebuffer, Error>
     {
-        if DEBUG_MODE && attachments.len() == 0 { panic!("Device::new_framebuffer: At least 1 attachment is required."); }
+        if DEBUG_MODE && attachments.len() == 0 { return Err(Error::Misuse(String::from("Device::new_framebuffer: At least 1 attachment is required."))); }
         let (width, height) = attachments[0].dimensions();
         let mut image_views = Vec::new();
         let mut attachments_vec = Vec::with_capacity(attachments.len());
         for attachment in attachments.iter()
         {
-        	if DEBUG_MODE && attachment.dimensions() != (width, height) { panic!("Swapchain::new_framebuffers: Inconsistent dimensions."); }
+        	if DEBUG_MODE && attachment.dimensions() != (width, height) { return Err(Error::Misuse(String::from("Device::new_framebuffer: Inconsistent dimensions."))); }
         	match attachment
         	{
         		FramebufferAttachment::Swapchain(SwapchainImage { image_view, .. }) => attachments_vec.push(**image_view),
         		FramebufferAttachment::Image { image, layer } =>
         		{
-        			if let ImageUsage::Texture { .. } = image.image_usage { panic!("Swapchain::new_framebuffers: Texture cannot be used as attachment."); }
+        			if let ImageUsage::Texture { .. } = image.image_usage { return Err(Error::Misuse(String::from("Device::new_framebuffer: Texture cannot be used as attachment."))); }
                     if let Some(layer) = layer
                     {
                         let image_view_create_info = vk::ImageViewCreateInfo::default()
@@ -299,7 +299,16 @@ impl Device
                                 base_array_layer: *layer,
                                 layer_count: 1
                             });
-                        let image_view = unsafe { self.0.logical_device.create_image_view(&image_view_create_info, None) }.unwrap();
+                        let image_view = unsafe { self.0.logical_device.create_image_view(&image_view_create_info, None) };
+                        let image_view = match image_view
+                        {
+                            Ok(image_view) => image_view,
+                            Err(error) =>
+                            {
+                                for image_view in &image_views { unsafe { self.0.logical_device.destroy_image_view(*image_view, None); } }
+                                return Err(error.into());
+                            }
+                        };
                         image_views.push(image_view);
                         attachments_vec.push(image_view);
                     } else { attachments_vec.push(image.image_view); }
@@ -312,8 +321,16 @@ impl Device
             .width(width)
             .height(height)
             .layers(1);
-        let framebuffer = unsafe { self.0.logical_device.create_framebuffer(&framebuffer_info, None) }.unwrap();
-        Framebuffer { device: self.0.clone(), image_views, framebuffer, size: (width, height) }
+        let framebuffer = match unsafe { self.0.logical_device.create_framebuffer(&framebuffer_info, None) }
+        {
+            Ok(framebuffer) => framebuffer,
+            Err(error) =>
+            {
+                for image_view in &image_views { unsafe { self.0.logical_device.destroy_image_view(*image_view, None); } }
+                return Err(error.into());
+            }
+        };
+        Ok(Framebuffer { device: self.0.clone(), image_views, framebuffer, size: (width, height) })
     }
 }
 
@@ -427,7 +444,8 @@ impl AttachmentStore
 #[derive(Clone, Copy)]
 pub enum RenderPassColorAttachment
 {
-    Swapchain(SwapchainLoad),
+    //see Swapchain::color_attachment
+    Swapchain { format: SwapchainFormat, load: SwapchainLoad },
     Image
     {
         image_channel_type: ImageChannelType,
@@ -1,41 +1,192 @@
 use super::*;
 
+impl SwapchainFormat
+{
+    pub const ALL: &'static [SwapchainFormat] = &[Self::Srgb, Self::Unorm, Self::Hdr10, Self::ExtendedSrgb];
+
+    pub const fn image_channel_type(&self) -> ImageChannelType
+    {
+        match self
+        {
+            Self::Srgb => ImageChannelType::BgraSrgb,
+            Self::Unorm => ImageChannelType::BgraUnorm,
+            Self::Hdr10 => ImageChannelType::A2Bgr10Unorm,
+            Self::ExtendedSrgb => ImageChannelType::RgbaShalffloat
+        }
+    }
+
+    pub(crate) const fn vk_color_space(&self) -> vk::ColorSpaceKHR
+    {
+        match self
+        {
+            Self::Srgb | Self::Unorm => vk::ColorSpaceKHR::SRGB_NONLINEAR,
+            Self::Hdr10 => vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+            Self::ExtendedSrgb => vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT
+        }
+    }
+}
+
+impl PresentMode
+{
+    pub const ALL: &'static [PresentMode] = &[Self::Immediate, Self::Mailbox, Self::Fifo, Self::FifoRelaxed];
+
+    const fn vk_present_mode(&self) -> vk::PresentModeKHR
+    {
+        match self
+        {
+            Self::Immediate => vk::PresentModeKHR::IMMEDIATE,
+            Self::Mailbox => vk::PresentModeKHR::MAILBOX,
+            Self::Fifo => vk::PresentModeKHR::FIFO,
+            Self::FifoRelaxed => vk::PresentModeKHR::FIFO_RELAXED
+        }
+    }
+}
+
+impl CompositeAlpha
+{
+    pub const ALL: &'static [CompositeAlpha] = &[Self::Opaque, Self::PreMultiplied, Self::PostMultiplied, Self::Inherit];
+
+    const fn vk_composite_alpha(&self) -> vk::CompositeAlphaFlagsKHR
+    {
+        match self
+        {
+            Self::Opaque => vk::CompositeAlphaFlagsKHR::OPAQUE,
+            Self::PreMultiplied => vk::CompositeAlphaFlagsKHR::PRE_MULTIPLIED,
+            Self::PostMultiplied => vk::CompositeAlphaFlagsKHR::POST_MULTIPLIED,
+            Self::Inherit => vk::CompositeAlphaFlagsKHR::INHERIT
+        }
+    }
+}
+
+impl SwapchainUsage
+{
+    pub(crate) fn vk_image_usage(&self) -> vk::ImageUsageFlags
+    {
+        let mut usage = vk::ImageUsageFlags::COLOR_ATTACHMENT;
+        if self.transfer_src { usage |= vk::ImageUsageFlags::TRANSFER_SRC; }
+        if self.transfer_dst { usage |= vk::ImageUsageFlags::TRANSFER_DST; }
+        if self.storage { usage |= vk::ImageUsageFlags::STORAGE; }
+        usage
+    }
+}
+
+impl<'a> SwapchainInfo<'a>
+{
+    //sRGB, v-sync, 3 images, transfer source, opaque
+    pub fn new(surface: &'a Surface) -> Self
+    {
+        Self
+        {
+            surface,
+            formats: &[SwapchainFormat::Srgb],
+            present_modes: &[PresentMode::FifoRelaxed, PresentMode::Fifo],
+            image_count: 3,
+            usage: SwapchainUsage { transfer_src: true, transfer_dst: false, storage: false },
+            composite_alpha: CompositeAlpha::Opaque
+        }
+    }
+}
+
 impl Device
 {
-    pub fn new_swapchain(&self, old_swapchain: Option<Swapchain>, v_sync: bool) -> Option<Swapchain>
-    {
-        std::mem::drop(old_swapchain);
-        let surface = self.0.instance.surface.as_ref().expect("Device::new_swapchain: A swapchain needs a surface.");
-        let surface_loader = &surface.loader;
-        let surface = &surface.surface;
-        let surface_capabilities = unsafe { surface_loader.get_physical_device_surface_capabilities(self.0.physical_device, *surface) }.unwrap();
-        if surface_capabilities.current_extent.width == 0 || surface_capabilities.current_extent.height == 0 { return None; }
-        let present_modes = unsafe { surface_loader.get_physical_device_surface_present_modes(self.0.physical_device, *surface) }.unwrap();
-        let v_sync_mode =
-            if present_modes.iter().any(|mode| *mode == vk::PresentModeKHR::FIFO_RELAXED) { vk::PresentModeKHR::FIFO_RELAXED }
-            else { vk::PresentModeKHR::FIFO };
-        let no_v_sync_mode =
-            if present_modes.iter().any(|mode| *mode == vk::PresentModeKHR::IMMEDIATE) { vk::PresentModeKHR::IMMEDIATE }
-            else if present_modes.iter().any(|mode| *mode == vk::PresentModeKHR::MAILBOX) { vk::PresentModeKHR::MAILBOX }
-            else { vk::PresentModeKHR::FIFO };
-        let min_image_count = surface_capabilities.min_image_count;
-        let max_image_count = surface_capabilities.max_image_count.max(min_image_count);
+    pub fn surface_capabilities(&self, surface: &Surface) -> Result<SurfaceCapabilities, Error>
+    {
+        self.query_surface_capabilities(surface).map(|(capabilities, _)| capabilities)
+    }
+
+    //also returns the current transform needed to create a swapchain
+    fn query_surface_capabilities(&self, surface: &Surface) -> Result<(SurfaceCapabilities, vk::SurfaceTransformFlagsKHR), Error>
+    {
+        let surface_loader = &surface.0.loader;
+        let capabilities = unsafe { surface_loader.get_physical_device_surface_capabilities(self.0.physical_device, surface.0.surface) }?;
+        let surface_formats = unsafe { surface_loader.get_physical_device_surface_formats(self.0.physical_device, surface.0.surface) }?;
+        let present_modes = unsafe { surface_loader.get_physical_device_surface_present_modes(self.0.physical_device, surface.0.surface) }?;
+        let formats = SwapchainFormat::ALL.iter()
+            .filter(|format| surface_formats.iter().any(|surface_format|
+                surface_format.format == format.image_channel_type().vk_format() && surface_format.color_space == format.vk_color_space()))
+            .copied().collect();
+        Ok((SurfaceCapabilities
+        {
+            min_image_count: capabilities.min_image_count,
+            max_image_count: if capabilities.max_image_count == 0 { None } else { Some(capabilities.max_image_count) },
+            current_extent: if capabilities.current_extent.width == u32::MAX { None } else { Some((capabilities.current_extent.width, capabilities.current_extent.height)) },
+            min_extent: (capabilities.min_image_extent.width, capabilities.min_image_extent.height),
+            max_extent: (capabilities.max_image_extent.width, capabilities.max_image_extent.height),
+            formats,
+            present_modes: PresentMode::ALL.iter().filter(|mode| present_modes.contains(&mode.vk_present_mode())).copied().collect(),
+            usage: SwapchainUsage
+            {
+                transfer_src: capabilities.supported_usage_flags.contains(vk::ImageUsageFlags::TRANSFER_SRC),
+                transfer_dst: capabilities.supported_usage_flags.contains(vk::ImageUsageFlags::TRANSFER_DST),
+                storage: capabilities.supported_usage_flags.contains(vk::ImageUsageFlags::STORAGE)
+            },
+            composite_alpha: CompositeAlpha::ALL.iter().filter(|alpha| capabilities.supported_composite_alpha.contains(alpha.vk_composite_alpha())).copied().collect()
+        }, capabilities.current_transform))
+    }
+
+    //on success the old swapchain is handed to the driver and kept alive as retired by the new one,
+    //without a new swapchain (zero extent or error) it is left in place
+    pub fn new_swapchain(&self, old_swapchain: &mut Option<Swapchain>, info: &SwapchainInfo) -> Result<Option<Swapchain>, Error>
+    {
+        let swapchain = self.create_swapchain(old_swapchain.as_ref(), info)?;
+        Ok(swapchain.map(|mut swapchain| { swapchain.retired = old_swapchain.take().map(Box::new); swapchain }))
+    }
+
+    fn create_swapchain(&self, old_swapchain: Option<&Swapchain>, info: &SwapchainInfo) -> Result<Option<Swapchain>, Error>
+    {
+        if let Some(old_swapchain) = old_swapchain && !Arc::ptr_eq(&old_swapchain.surface, &info.surface.0)
+        {
+            return Err(Error::Misuse(String::from("Device::new_swapchain: The old swapchain belongs to another surface.")));
+        }
+        if !self.0.swapchain_support { return Err(Error::Misuse(String::from("Device::new_swapchain: The device was created without swapchain support."))); }
+        let (capabilities, current_transform) = self.query_surface_capabilities(info.surface)?;
+        let format = *info.formats.iter().find(|format| capabilities.formats.contains(format)).ok_or(Error::UnsupportedFormat)?;
+        let present_mode = *info.present_modes.iter().find(|mode| capabilities.present_modes.contains(mode)).unwrap_or(&PresentMode::Fifo);
+        if (info.usage.transfer_src && !capabilities.usage.transfer_src)
+        || (info.usage.transfer_dst && !capabilities.usage.transfer_dst)
+        || (info.usage.storage && !capabilities.usage.storage)
+        || !capabilities.composite_alpha.contains(&info.composite_alpha)
+            { return Err(Error::UnsupportedFeature); }
+        let extent = match (capabilities.current_extent, info.surface.0.extent)
+        {
+            (Some((width, height)), _) => vk::Extent2D { width, height },
+            //the surface leaves the extent to the swapchain
+            (None, Some((width, height))) => vk::Extent2D
+            {
+                width: width.clamp(capabilities.min_extent.0, capabilities.max_extent.0),
+                height: height.clamp(capabilities.min_extent.1, capabilities.max_extent.1)
+            },
+            (None, None) => return Err(Error::Misuse(String::from("Device::new_swapchain: The surface does not report its extent.")))
+        };
+        if extent.width == 0 || extent.height == 0 { return Ok(None); }
+        let image_count = info.image_count.max(capabilities.min_image_count).min(capabilities.max_image_count.unwrap_or(u32::MAX));
+        let raw_surface = info.surface.0.clone();
         let swapchain_create_info = vk::SwapchainCreateInfoKHR::default()
-            .surface(*surface)
-            .min_image_count(3.max(min_image_count).min(max_image_count))
-            .image_format(Swapchain::IMAGE_CHANNEL_TYPE.vk_format())
-            .image_color_space(vk::ColorSpaceKHR::SRGB_NONLINEAR)
-            .image_extent(surface_capabilities.current_extent)
+            .surface(raw_surface.surface)
+            .min_image_count(image_count)
+            .image_format(format.image_channel_type().vk_format())
+            .image_color_space(format.vk_color_space())
+            .image_extent(extent)
             .image_array_layers(1)
-            .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC)
+            .image_usage(info.usage.vk_image_usage())
             .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
-            .pre_transform(surface_capabilities.current_transform)
-            .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
-            .present_mode(if v_sync { v_sync_mode } else { no_v_sync_mode });
-        let swapchain_loader = ash::khr::swapchain::Device::new(&self.0.instance.instance, &self.0.logical_device);
-        let swapchain = unsafe { swapchain_loader.create_swapchain(&swapchain_create_info, None).unwrap() };
-        let swapchain_images: Box<[vk::Image]> = Box::from(unsafe { swapchain_loader.get_swapchain_images(swapchain) }.unwrap());
-        let swapchain_image_views: Box<_> = swapchain_images.iter().map(|image|
+            .pre_transform(current_transform)
+            .composite_alpha(info.composite_alpha.vk_composite_alpha())
+            .present_mode(present_mode.vk_present_mode())
+            .old_swapchain(old_swapchain.map_or(vk::SwapchainKHR::null(), |old_swapchain| old_swapchain.swapchain));
+        let swapchain_loader = ash::khr::swapchain::Device::new(&self.0.instance.0.instance, &self.0.logical_device);
+        let swapchain = unsafe { swapchain_loader.create_swapchain(&swapchain_create_info, None) }?;
+        let swapchain_images: Box<[vk::Image]> = match unsafe { swapchain_loader.get_swapchain_images(swapchain) }
+        {
+            Ok(swapchain_images) => Box::from(swapchain_images),
+            Err(error) =>
+            {
+                unsafe { swapchain_loader.destroy_swapchain(swapchain, None); }
+                return Err(error.into());
+            }
+        };
+        let mut swapchain_image_views = Vec::with_capacity(swapchain_images.len());
+        for image in swapchain_images.iter()
         {
             let subresource_range = vk::ImageSubresourceRange::default()
                 .aspect_mask(vk::ImageAspectFlags::COLOR)
@@ -46,43 +197,72 @@ impl Device
             let image_view_create_info = vk::ImageViewCreateInfo::default()
                 .image(*image)
                 .view_type(vk::ImageViewType::TYPE_2D)
-                .format(Swapchain::IMAGE_CHANNEL_TYPE.vk_format())
+                .format(format.image_channel_type().vk_format())
                 .subresource_range(subresource_range);
-            unsafe { self.0.logical_device.create_image_view(&image_view_create_info, None) }.unwrap()
-        }).collect();
+            match unsafe { self.0.logical_device.create_image_view(&image_view_create_info, None) }
+            {
+                Ok(image_view) => swapchain_image_views.push(image_view),
+                Err(error) => unsafe
+                {
+                    for image_view in &swapchain_image_views { self.0.logical_device.destroy_image_view(*image_view, None); }
+                    swapchain_loader.destroy_swapchain(swapchain, None);
+                    return Err(error.into());
+                }
+            }
+        }
+        let swapchain_image_views = swapchain_image_views.into_boxed_slice();
         let count = swapchain_images.len();
 
-        let vk::Extent2D { width, height } = surface_capabilities.current_extent;
+        let vk::Extent2D { width, height } = extent;
         let swapchain = Swapchain
         {
             device: self.0.clone(),
+            surface: raw_surface,
+            format, present_mode,
+            usage: info.usage,
             width, height,
             swapchain_loader, swapchain,
             swapchain_images, swapchain_image_views,
-            count, cycle_index: std::cell::Cell::new(0)
+            count, cycle_index: std::cell::Cell::new(0),
+            retired: None
         };
-        Some(swapchain)
+        Ok(Some(swapchain))
     }
 }
 
 impl Swapchain
 {
-    pub const IMAGE_CHANNEL_TYPE: ImageChannelType = ImageChannelType::BgraSrgb; //assumes linear shader input
+    pub fn format(&self) -> SwapchainFormat { self.format }
+    pub fn present_mode(&self) -> PresentMode { self.present_mode }
+    pub fn usage(&self) -> SwapchainUsage { self.usage }
+    pub fn image_count(&self) -> usize { self.count }
 
+    //a render pass attachment matching the format of this swapchain
+    pub fn color_attachment(&self, load: SwapchainLoad) -> RenderPassColorAttachment
+    {
+        RenderPassColorAttachment::Swapchain { format: self.format, load }
+    }
+
+    //waits forever without a timeout
     #[inline]
-    pub fn acquire_next_image(&self, signal: Option<&Semaphore>, mark: Option<&Fence>) -> Result<SwapchainObjectIndex, ()>
+    pub fn acquire_next_image(&self, signal: Option<&Semaphore>, mark: Option<&Fence>, timeout: Option<std::time::Duration>) -> Result<AcquireOutcome, Error>
     {
         let semaphore = signal.map(|semaphore| semaphore.semaphore).unwrap_or(vk::Semaphore::null());
         let fence = mark.map(|mark| mark.fence).unwrap_or(vk::Fence::null());
-        match unsafe { self.swapchain_loader.acquire_next_image(self.swapchain, std::u64::MAX, semaphore, fence) }
+        let timeout = timeout.map_or(u64::MAX, |timeout| timeout.as_nanos().min(u64::MAX as u128) as u64);
+        match unsafe { self.swapchain_loader.acquire_next_image(self.swapchain, timeout, semaphore, fence) }
         {
-            Ok((image_index, suboptimal)) => if !suboptimal { Ok(SwapchainObjectIndex { index: image_index as usize }) } else { Err(()) },
-            Err(_) => Err(())
+            Ok((image_index, false)) => Ok(AcquireOutcome::Ok(SwapchainObjectIndex { index: image_index as usize })),
+            Ok((image_index, true)) => Ok(AcquireOutcome::Suboptimal(SwapchainObjectIndex { index: image_index as usize })),
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(AcquireOutcome::OutOfDate),
+            Err(vk::Result::ERROR_SURFACE_LOST_KHR) => Ok(AcquireOutcome::SurfaceLost),
+            Err(vk::Result::TIMEOUT | vk::Result::NOT_READY) => Ok(AcquireOutcome::Timeout),
+            Err(error) => Err(error.into())
         }
     }
 
     #[inline]
-    pub fn present<const N: usize>(&self, index: SwapchainObjectIndex, queue: &Queue, wait: [&Semaphore; N]) -> bool
+    pub fn present<const N: usize>(&self, index: SwapchainObjectIndex, queue: &Queue, wait: [&Semaphore; N]) -> Result<PresentOutcome, Error>
     {
         self.cycle_index.set((self.cycle_index.get() + 1) % self.count);
         let semaphores = wait.map(|semaphore| semaphore.semaphore);
@@ -92,7 +272,16 @@ impl Swapchain
             .wait_semaphores(&semaphores)
             .swapchains(swapchains)
             .image_indices(image_indices);
-        unsafe { self.swapchain_loader.queue_present(queue.queue, &present_info) }.ok().is_some()
+        let result = unsafe { self.swapchain_loader.queue_present(queue.queue, &present_info) };
+        self.device.instance.0.raise_debug_error();
+        match result
+        {
+            Ok(false) => Ok(PresentOutcome::Ok),
+            Ok(true) => Ok(PresentOutcome::Suboptimal),
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(PresentOutcome::OutOfDate),
+            Err(vk::Result::ERROR_SURFACE_LOST_KHR) => Ok(PresentOutcome::SurfaceLost),
+            Err(error) => Err(error.into())
+        }
     }
 
     pub fn get_image<'a>(&'a self, index: &'a SwapchainObjectIndex) -> SwapchainImage<'a>
@@ -101,6 +290,8 @@ impl Swapchain
         {
             image: &self.swapchain_images[index.index],
             image_view: &self.swapchain_image_views[index.index],
+            format: self.format,
+            usage: self.usage,
             width: self.width,
             height: self.height
         }
@@ -112,6 +303,49 @@ impl Swapchain
     {
         SwapchainObjects { objects: (0..self.count).map(|index| constructor(&SwapchainObjectIndex { index })).collect() }
     }
+
+    //replaces this swapchain and keeps the old one as retired, false if no swapchain could be created (zero extent)
+    pub fn recreate(&mut self, info: &SwapchainInfo) -> Result<bool, Error>
+    {
+        match Device(self.device.clone()).create_swapchain(Some(self), info)?
+        {
+            Some(swapchain) =>
+            {
+                let old_swapchain = std::mem::replace(self, swapchain);
+                self.retired = Some(Box::new(old_swapchain));
+                Ok(true)
+            },
+            None => Ok(false)
+        }
+    }
+
+    //also rebuilds the objects depending on the swapchain, they are left untouched if no swapchain is created
+    pub fn recreate_with_objects<T>(&mut self, info: &SwapchainInfo, objects: &mut SwapchainObjects<T>, constructor: &mut dyn FnMut(&Swapchain, &SwapchainObjectIndex) -> T) -> Result<bool, Error>
+    {
+        let recreated = self.recreate(info)?;
+        if recreated { *objects = self.new_objects(&mut |index| constructor(self, index)); }
+        Ok(recreated)
+    }
+
+    //destroys all replaced swapchains, call once the frames presenting their images have finished
+    pub fn drop_retired(&mut self)
+    {
+        self.retired = None;
+    }
+}
+
+impl PresentTarget for Swapchain
+{
+    fn acquire_next_image(&self, signal: Option<&Semaphore>, mark: Option<&Fence>, timeout: Option<std::time::Duration>) -> Result<AcquireOutcome, Error> { self.acquire_next_image(signal, mark, timeout) }
+    fn present<const N: usize>(&self, index: SwapchainObjectIndex, queue: &Queue, wait: [&Semaphore; N]) -> Result<PresentOutcome, Error> { self.present(index, queue, wait) }
+    fn get_image<'a>(&'a self, index: &'a SwapchainObjectIndex) -> SwapchainImage<'a> { self.get_image(index) }
+    fn dimensions(&self) -> (u32, u32) { self.dimensions() }
+    fn format(&self) -> SwapchainFormat { self.format() }
+    fn usage(&self) -> SwapchainUsage { self.usage() }
+    fn image_count(&self) -> usize { self.image_count() }
+    fn color_attachment(&self, load: SwapchainLoad) -> RenderPassColorAttachment { self.color_attachment(load) }
+    fn new_objects<T>(&self, constructor: &mut dyn FnMut(&SwapchainObjectIndex) -> T) -> SwapchainObjects<T> { self.new_objects(constructor) }
+    fn drop_retired(&mut self) { self.drop_retired() }
 }
 
 pub struct SwapchainObjectIndex
@@ -119,9 +353,30 @@ pub struct SwapchainObjectIndex
     pub(crate) index: usize
 }
 
+pub enum AcquireOutcome
+{
+    Ok(SwapchainObjectIndex),
+    //the image can still be rendered to and presented, but the swapchain should be recreated
+    Suboptimal(SwapchainObjectIndex),
+    //the swapchain has to be recreated
+    OutOfDate,
+    //the surface has to be recreated
+    SurfaceLost,
+    Timeout
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PresentOutcome
+{
+    Ok,
+    Suboptimal,
+    OutOfDate,
+    SurfaceLost
+}
+
 pub struct SwapchainObjects<T>
 {
-    objects: Box<[T]>
+    pub(crate) objects: Box<[T]>
 }
 
 impl<T> SwapchainObjects<T>
@@ -201,3 +456,210 @@ impl<const N: usize, T> SwapchainCycle<N, T>
         self.get_current()
     }
 }
+
+struct Frame<T>
+{
+    image_available: Semaphore,
+    render_finished: Semaphore,
+    in_flight: Fence,
+    command_buffer: CommandBuffer,
+    data: T
+}
+
+pub struct FrameRecord<'a, T, S = Swapchain>
+{
+    pub index: SwapchainObjectIndex,
+    //the frame in flight (0..N) whose previous submission has finished, e.g. for StagingRing::begin_frame
+    pub frame: usize,
+    pub swapchain: &'a S,
+    //ready to record, submitted by FrameLoop::end_frame
+    pub command_buffer: &'a mut CommandBuffer,
+    //reset and signaled by the submission in FrameLoop::end_frame
+    pub in_flight: &'a Fence,
+    pub data: &'a mut T,
+    //objects depending on the swapchain have to be rebuilt
+    pub recreated: bool
+}
+
+//replaces the swapchain in place, false if none can be created right now
+type RecreateSwapchain<S> = Box<dyn FnMut(&mut Option<S>) -> Result<bool, Error>>;
+
+//owns the per-frame synchronization of N frames in flight, the same frame code runs on a Swapchain or an OffscreenSwapchain
+pub struct FrameLoop<const N: usize, T = (), S: PresentTarget = Swapchain>
+{
+    queue: Arc<Mutex<Queue>>,
+    recreate_swapchain: RecreateSwapchain<S>,
+    //None while the surface has no extent (e.g. a minimized window)
+    swapchain: Option<S>,
+    frames: SwapchainCycle<N, Frame<T>>,
+    frames_since_recreation: usize,
+    recreate: bool,
+    recreated: bool
+}
+
+impl<const N: usize, T> FrameLoop<N, T>
+{
+    pub fn new(device: &Device, queue_family: &QueueFamily, queue: Arc<Mutex<Queue>>, info: &SwapchainInfo, constructor: &mut dyn FnMut() -> T) -> Result<Self, Error>
+    {
+        let swapchain = device.new_swapchain(&mut None, info)?;
+        let (device_clone, surface, formats, present_modes) = (device.clone(), info.surface.clone(), info.formats.to_vec(), info.present_modes.to_vec());
+        let (image_count, usage, composite_alpha) = (info.image_count, info.usage, info.composite_alpha);
+        let recreate_swapchain = Box::new(move |swapchain: &mut Option<Swapchain>|
+        {
+            let info = SwapchainInfo { surface: &surface, formats: &formats, present_modes: &present_modes, image_count, usage, composite_alpha };
+            //without a new swapchain the old one stays, its frames may still be in flight
+            match device_clone.new_swapchain(swapchain, &info)?
+            {
+                Some(new_swapchain) =>
+                {
+                    *swapchain = Some(new_swapchain);
+                    Ok(true)
+                },
+                None => Ok(false)
+            }
+        });
+        Self::from_swapchain(device, queue_family, queue, swapchain, recreate_swapchain, constructor)
+    }
+}
+
+impl<const N: usize, T> FrameLoop<N, T, OffscreenSwapchain>
+{
+    //OffscreenSwapchain::present waits for the readback of the previous frame,
+    //so only one frame is in flight no matter how large N is
+    pub fn new_offscreen(device: &Device, queue_family: &QueueFamily, queue: Arc<Mutex<Queue>>, swapchain: OffscreenSwapchain, constructor: &mut dyn FnMut() -> T) -> Result<Self, Error>
+    {
+        //offscreen images never go out of date, a requested recreation keeps them
+        Self::from_swapchain(device, queue_family, queue, Some(swapchain), Box::new(|swapchain: &mut Option<OffscreenSwapchain>| Ok(swapchain.is_some())), constructor)
+    }
+}
+
+impl<const N: usize, T, S: PresentTarget> FrameLoop<N, T, S>
+{
+    fn from_swapchain(device: &Device, queue_family: &QueueFamily, queue: Arc<Mutex<Queue>>, swapchain: Option<S>, recreate_swapchain: RecreateSwapchain<S>, constructor: &mut dyn FnMut() -> T) -> Result<Self, Error>
+    {
+        let pool = device.new_command_pool(queue_family)?;
+        let mut frames = Vec::with_capacity(N);
+        for _ in 0..N
+        {
+            frames.push(Frame
+            {
+                image_available: device.new_semaphore(WaitStage::ColorOutput)?,
+                render_finished: device.new_semaphore(WaitStage::None)?,
+                in_flight: device.new_fence(true)?,
+                command_buffer: pool.new_command_buffer()?,
+                data: constructor()
+            });
+        }
+        let objects = match frames.try_into() { Ok(objects) => objects, Err(_) => unreachable!() };
+        Ok(Self
+        {
+            queue,
+            recreate_swapchain,
+            swapchain,
+            frames: SwapchainCycle { objects, index: 0 },
+            frames_since_recreation: 0,
+            recreate: false,
+            recreated: true
+        })
+    }
+
+    fn recreate_swapchain(&mut self) -> Result<(), Error>
+    {
+        if (self.recreate_swapchain)(&mut self.swapchain)?
+        {
+            self.frames_since_recreation = 0;
+            self.recreate = false;
+            self.recreated = true;
+        }
+        Ok(())
+    }
+
+    //forces a new swapchain at the next frame, e.g. after a window resize
+    pub fn request_recreation(&mut self)
+    {
+        self.recreate = true;
+    }
+
+    pub fn swapchain(&self) -> Option<&S> { self.swapchain.as_ref() }
+
+    pub fn frames(&mut self) -> impl Iterator<Item = &mut T>
+    {
+        self.frames.slice().iter_mut().map(|frame| &mut frame.data)
+    }
+
+    //None if no frame can be rendered right now, e.g. while the window is minimized
+    pub fn begin_frame(&mut self) -> Result<Option<FrameRecord<'_, T, S>>, Error>
+    {
+        let frame = self.frames.get_next();
+        frame.in_flight.wait();
+        //every frame using images of the retired swapchain has finished
+        self.frames_since_recreation += 1;
+        if self.frames_since_recreation > N && let Some(swapchain) = &mut self.swapchain
+        {
+            swapchain.drop_retired();
+        }
+        if self.recreate || self.swapchain.is_none() { self.recreate_swapchain()?; }
+        //the old swapchain is kept while no new one can be created
+        let swapchain = match &self.swapchain
+        {
+            Some(swapchain) if !self.recreate => swapchain,
+            _ => return Ok(None)
+        };
+        let frame_index = self.frames.index;
+        let frame = self.frames.get_current();
+        let index = match swapchain.acquire_next_image(Some(&frame.image_available), None, None)?
+        {
+            AcquireOutcome::Ok(index) => index,
+            AcquireOutcome::Suboptimal(index) =>
+            {
+                self.recreate = true;
+                index
+            },
+            AcquireOutcome::OutOfDate =>
+            {
+                self.recreate = true;
+                return Ok(None);
+            },
+            AcquireOutcome::SurfaceLost => return Err(Error::SurfaceLost),
+            AcquireOutcome::Timeout => return Ok(None)
+        };
+        let recreated = std::mem::replace(&mut self.recreated, false);
+        Ok(Some(FrameRecord
+        {
+            index,
+            frame: frame_index,
+            swapchain: self.swapchain.as_ref().unwrap(),
+            command_buffer: &mut frame.command_buffer,
+            in_flight: &frame.in_flight,
+            data: &mut frame.data,
+            recreated
+        }))
+    }
+
+    //submits the command buffer of the current frame and presents the image
+    pub fn end_frame(&mut self, index: SwapchainObjectIndex) -> Result<(), Error>
+    {
+        let swapchain = self.swapchain.as_ref().ok_or_else(|| Error::Misuse(String::from("FrameLoop::end_frame: There is no frame in progress.")))?;
+        let frame = self.frames.get_current();
+        let queue = self.queue.lock().unwrap();
+        //reset only here, so the fences of frames that were never submitted stay signaled
+        frame.in_flight.reset();
+        frame.command_buffer.submit(&queue, [&frame.image_available], [&frame.render_finished], Some(&frame.in_flight))?;
+        match swapchain.present(index, &queue, [&frame.render_finished])?
+        {
+            PresentOutcome::Ok => {},
+            PresentOutcome::Suboptimal | PresentOutcome::OutOfDate => self.recreate = true,
+            PresentOutcome::SurfaceLost => return Err(Error::SurfaceLost)
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize, T, S: PresentTarget> Drop for FrameLoop<N, T, S>
+{
+    //the swapchain and the per-frame objects may still be used by frames in flight
+    fn drop(&mut self)
+    {
+        for frame in self.frames.slice().iter() { frame.in_flight.wait(); }
+    }
+}